@@ -0,0 +1,198 @@
+use gpui::Rgba;
+
+use crate::color::clamp_rgb;
+use crate::gradient::Interpolation;
+
+/// How color B is combined with color A. `Normal` interpolates between them;
+/// the others are the separable blend modes of CSS `mix-blend-mode`, with B
+/// layered over A.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BlendMode {
+    #[default]
+    Normal,
+    Multiply,
+    Screen,
+    Overlay,
+    SoftLight,
+}
+
+impl BlendMode {
+    pub const ALL: [BlendMode; 5] = [
+        BlendMode::Normal,
+        BlendMode::Multiply,
+        BlendMode::Screen,
+        BlendMode::Overlay,
+        BlendMode::SoftLight,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            BlendMode::Normal => "Normal",
+            BlendMode::Multiply => "Multiply",
+            BlendMode::Screen => "Screen",
+            BlendMode::Overlay => "Overlay",
+            BlendMode::SoftLight => "Soft light",
+        }
+    }
+
+    // The blend function B(backdrop, source) from the Compositing and Blending
+    // spec, for one sRGB channel
+    fn channel(self, backdrop: f32, source: f32) -> f32 {
+        let multiply = |b: f32, s: f32| b * s;
+        let screen = |b: f32, s: f32| b + s - b * s;
+        match self {
+            BlendMode::Normal => source,
+            BlendMode::Multiply => multiply(backdrop, source),
+            BlendMode::Screen => screen(backdrop, source),
+            BlendMode::Overlay => {
+                if backdrop <= 0.5 {
+                    multiply(source, 2.0 * backdrop)
+                } else {
+                    screen(source, 2.0 * backdrop - 1.0)
+                }
+            }
+            BlendMode::SoftLight => {
+                if source <= 0.5 {
+                    backdrop - (1.0 - 2.0 * source) * backdrop * (1.0 - backdrop)
+                } else {
+                    let d = if backdrop <= 0.25 {
+                        ((16.0 * backdrop - 12.0) * backdrop + 4.0) * backdrop
+                    } else {
+                        backdrop.sqrt()
+                    };
+                    backdrop + (2.0 * source - 1.0) * (d - backdrop)
+                }
+            }
+        }
+    }
+}
+
+/// Combines `a` and `b` at `ratio` 0..1. For `Normal` the ratio moves from A to
+/// B in `space`; for the other modes it is the opacity of B over A, which the
+/// blend modes always compute in sRGB as browsers do.
+pub fn blend(a: Rgba, b: Rgba, mode: BlendMode, space: Interpolation, ratio: f32) -> Rgba {
+    let ratio = ratio.clamp(0.0, 1.0);
+    if mode == BlendMode::Normal {
+        return space.mix(a, b, ratio);
+    }
+
+    let source_alpha = b.a * ratio;
+    let backdrop_alpha = a.a;
+    let alpha = source_alpha + backdrop_alpha * (1.0 - source_alpha);
+    if alpha == 0.0 {
+        return Rgba {
+            r: 0.0,
+            g: 0.0,
+            b: 0.0,
+            a: 0.0,
+        };
+    }
+    let composite = |backdrop: f32, source: f32| {
+        // Where the backdrop is transparent the source shows unblended
+        let blended =
+            (1.0 - backdrop_alpha) * source + backdrop_alpha * mode.channel(backdrop, source);
+        (source_alpha * blended + backdrop_alpha * backdrop * (1.0 - source_alpha)) / alpha
+    };
+    clamp_rgb(Rgba {
+        r: composite(a.r, b.r),
+        g: composite(a.g, b.g),
+        b: composite(a.b, b.b),
+        a: alpha,
+    })
+}
+
+/// `count` results at evenly spaced ratios from 0 (A) to 1
+pub fn steps(a: Rgba, b: Rgba, mode: BlendMode, space: Interpolation, count: usize) -> Vec<Rgba> {
+    match count {
+        0 => Vec::new(),
+        1 => vec![blend(a, b, mode, space, 0.5)],
+        _ => (0..count)
+            .map(|index| blend(a, b, mode, space, index as f32 / (count - 1) as f32))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::{rgb, rgba};
+
+    use super::*;
+    use crate::color::to_hex;
+
+    fn hex(a: u32, b: u32, mode: BlendMode) -> String {
+        to_hex(blend(rgb(a), rgb(b), mode, Interpolation::Srgb, 1.0))
+    }
+
+    #[test]
+    fn blend_modes_match_css() {
+        // Worked through the spec formulas by hand
+        assert_eq!(hex(0x336699, 0x808080, BlendMode::Multiply), "#1a334d");
+        assert_eq!(hex(0x336699, 0x808080, BlendMode::Screen), "#99b3cc");
+        assert_eq!(hex(0x336699, 0xffffff, BlendMode::Multiply), "#336699");
+        assert_eq!(hex(0x336699, 0x000000, BlendMode::Screen), "#336699");
+        assert_eq!(hex(0x336699, 0xcccccc, BlendMode::Overlay), "#52a3d6");
+        assert_eq!(hex(0x336699, 0x7f7f7f, BlendMode::SoftLight), "#336699");
+        assert_eq!(hex(0x336699, 0xffffff, BlendMode::SoftLight), "#72a1c6");
+    }
+
+    #[test]
+    fn ratio_fades_the_blended_layer_in() {
+        let (a, b) = (rgb(0xff0000), rgb(0x00ff00));
+        for mode in BlendMode::ALL {
+            assert_eq!(blend(a, b, mode, Interpolation::Srgb, 0.0), a);
+        }
+        assert_eq!(
+            to_hex(blend(a, b, BlendMode::Normal, Interpolation::Srgb, 0.5)),
+            "#808000"
+        );
+        assert_eq!(
+            to_hex(blend(a, b, BlendMode::Screen, Interpolation::Srgb, 0.5)),
+            "#ff8000"
+        );
+    }
+
+    #[test]
+    fn normal_mode_uses_the_chosen_space() {
+        let (a, b) = (rgb(0x000000), rgb(0xffffff));
+        let mid = |space| to_hex(blend(a, b, BlendMode::Normal, space, 0.5));
+        assert_eq!(mid(Interpolation::Srgb), "#808080");
+        assert_eq!(mid(Interpolation::Oklab), "#636363");
+    }
+
+    #[test]
+    fn translucent_layers_composite() {
+        // A half-transparent black scrim over white, as in a hover overlay
+        let scrim = blend(
+            rgb(0xffffff),
+            rgba(0x00000080),
+            BlendMode::Multiply,
+            Interpolation::Srgb,
+            1.0,
+        );
+        assert_eq!(to_hex(scrim), "#7f7f7f");
+        // Over nothing, B shows as it is
+        let alone = blend(
+            rgba(0x00000000),
+            rgb(0x336699),
+            BlendMode::Multiply,
+            Interpolation::Srgb,
+            1.0,
+        );
+        assert_eq!(to_hex(alone), "#336699");
+    }
+
+    #[test]
+    fn steps_run_from_a_to_the_full_blend() {
+        let colors = steps(
+            rgb(0x336699),
+            rgb(0x808080),
+            BlendMode::Multiply,
+            Interpolation::Srgb,
+            5,
+        );
+        assert_eq!(colors.len(), 5);
+        assert_eq!(to_hex(colors[0]), "#336699");
+        assert_eq!(to_hex(colors[4]), "#1a334d");
+        assert!(steps(rgb(0), rgb(0), BlendMode::Normal, Interpolation::Srgb, 0).is_empty());
+    }
+}
@@ -0,0 +1,24 @@
+use gpui::{Bounds, Canvas, Pixels, canvas, fill, point, px, rgb, size};
+
+const CELL_SIZE: Pixels = px(8.0);
+
+/// Light and dark squares that make translucent colors painted on top visible
+pub fn checkerboard() -> Canvas<()> {
+    canvas(
+        |_, _, _| {},
+        |bounds, _, window, _| {
+            window.paint_quad(fill(bounds, rgb(0xcccccc)));
+
+            let columns = (bounds.size.width / CELL_SIZE).ceil() as usize;
+            let rows = (bounds.size.height / CELL_SIZE).ceil() as usize;
+            for row in 0..rows {
+                for column in (row % 2..columns).step_by(2) {
+                    let origin =
+                        bounds.origin + point(CELL_SIZE * column as f32, CELL_SIZE * row as f32);
+                    let cell = Bounds::new(origin, size(CELL_SIZE, CELL_SIZE)).intersect(&bounds);
+                    window.paint_quad(fill(cell, rgb(0x999999)));
+                }
+            }
+        },
+    )
+}
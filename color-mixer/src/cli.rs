@@ -0,0 +1,392 @@
+use std::fmt;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use gpui::Rgba;
+
+use crate::contrast::{ContrastTarget, apca_contrast, contrast_ratio};
+use crate::format::ColorFormat;
+use crate::harmony::{self, Harmony, HarmonySpace};
+use crate::palette::{Palette, export::ExportFormat, import};
+use crate::parse::parse_color;
+use crate::scale::Scale;
+
+pub const USAGE: &str = "\
+Usage:
+  gpui-color-mixer                          open the mixer window
+  gpui-color-mixer convert <color> [--to <format>]
+      formats: hex, rgb, hsl, hsv, oklch, lab, rust, rust-rgba (default: all)
+  gpui-color-mixer contrast <text> <background>
+  gpui-color-mixer harmony <color> [--type <harmony>] [--space hsl|oklch]
+      harmonies: complementary, analogous, triadic, split, tetradic
+  gpui-color-mixer scale <color> [--name <name>] [--curve <0.5-2.0>] [--format css|tokens]
+  gpui-color-mixer export [<color>...] [--from <palette file>] [--name <name>]
+                          [--format css|gpl|ase|json|rust] [--output <file>]
+
+Colors are hex, rgb(), hsl() or CSS names, e.g. '#1976d2' or 'rebeccapurple'.";
+
+#[derive(Debug)]
+pub enum CliError {
+    // Bad invocation; the usage text is worth showing
+    Usage(String),
+    Invalid(String),
+    Io(String),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CliError::Usage(message) | CliError::Invalid(message) | CliError::Io(message) => {
+                write!(f, "{message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl From<io::Error> for CliError {
+    fn from(error: io::Error) -> Self {
+        CliError::Io(error.to_string())
+    }
+}
+
+/// Runs a subcommand and returns the process exit code, printing results to
+/// stdout and errors to stderr
+pub fn main(args: &[String]) -> i32 {
+    let mut stdout = io::stdout().lock();
+    match run(args, &mut stdout) {
+        Ok(()) => 0,
+        Err(CliError::Usage(message)) => {
+            eprintln!("error: {message}\n\n{USAGE}");
+            2
+        }
+        Err(error) => {
+            eprintln!("error: {error}");
+            1
+        }
+    }
+}
+
+/// Runs the subcommand named by `args[0]`, writing its output to `out`
+pub fn run(args: &[String], out: &mut dyn Write) -> Result<(), CliError> {
+    let Some((command, args)) = args.split_first() else {
+        return Err(CliError::Usage("missing subcommand".into()));
+    };
+    let args = Args::parse(args)?;
+    match command.as_str() {
+        "convert" => convert(&args, out),
+        "contrast" => contrast(&args, out),
+        "harmony" => harmony(&args, out),
+        "scale" => scale(&args, out),
+        "export" => export(&args, out),
+        "help" | "--help" | "-h" => Ok(writeln!(out, "{USAGE}")?),
+        _ => Err(CliError::Usage(format!("unknown subcommand `{command}`"))),
+    }
+}
+
+fn convert(args: &Args, out: &mut dyn Write) -> Result<(), CliError> {
+    args.allow_options(&["--to"])?;
+    let [color] = args.positional("convert", ["color"])?;
+    let color = color_arg(color)?;
+    match args.option("--to") {
+        Some(name) => {
+            let format = choose(name, "format", &ColorFormat::ALL, |format| {
+                vec![format.id()]
+            })?;
+            writeln!(out, "{}", format.format(color))?;
+        }
+        None => {
+            for format in ColorFormat::ALL {
+                writeln!(out, "{:<11}{}", format.label(), format.format(color))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn contrast(args: &Args, out: &mut dyn Write) -> Result<(), CliError> {
+    args.allow_options(&[])?;
+    let [text, background] = args.positional("contrast", ["text", "background"])?;
+    let (text, background) = (color_arg(text)?, color_arg(background)?);
+
+    writeln!(out, "Ratio  {:.2}:1", contrast_ratio(text, background))?;
+    for target in ContrastTarget::WCAG {
+        let verdict = if target.is_met(text, background) {
+            "pass"
+        } else {
+            "fail"
+        };
+        writeln!(out, "{:<10} {verdict}", target.label())?;
+    }
+    writeln!(out, "APCA   Lc {:.1}", apca_contrast(text, background))?;
+    Ok(())
+}
+
+fn harmony(args: &Args, out: &mut dyn Write) -> Result<(), CliError> {
+    args.allow_options(&["--type", "--space"])?;
+    let [color] = args.positional("harmony", ["color"])?;
+    let color = color_arg(color)?;
+    let kind = match args.option("--type") {
+        Some(name) => choose(name, "harmony", &Harmony::ALL, |harmony| {
+            vec![harmony.label()]
+        })?,
+        None => Harmony::default(),
+    };
+    let space = match args.option("--space") {
+        Some(name) => choose(name, "space", &HarmonySpace::ALL, |space| {
+            vec![space.label()]
+        })?,
+        None => HarmonySpace::default(),
+    };
+
+    for color in harmony::generate(color, kind, space) {
+        writeln!(out, "{}", ColorFormat::Hex.format(color))?;
+    }
+    Ok(())
+}
+
+fn scale(args: &Args, out: &mut dyn Write) -> Result<(), CliError> {
+    args.allow_options(&["--name", "--curve", "--format"])?;
+    let [color] = args.positional("scale", ["color"])?;
+    let color = color_arg(color)?;
+    let curve = match args.option("--curve") {
+        Some(curve) => curve
+            .parse::<f32>()
+            .ok()
+            .filter(|curve| (0.5..=2.0).contains(curve))
+            .ok_or_else(|| CliError::Usage(format!("curve `{curve}` is not in 0.5..2.0")))?,
+        None => 1.0,
+    };
+    let scale = Scale::new(color, curve);
+    let name = args.option("--name").unwrap_or("color");
+    let text = match args.option("--format") {
+        Some("tokens") => scale.to_tokens(name),
+        Some("css") | None => scale.to_css(name),
+        Some(format) => {
+            return Err(CliError::Usage(format!(
+                "unknown format `{format}`, expected one of: css, tokens"
+            )));
+        }
+    };
+    Ok(out.write_all(text.as_bytes())?)
+}
+
+fn export(args: &Args, out: &mut dyn Write) -> Result<(), CliError> {
+    args.allow_options(&["--from", "--name", "--format", "--output"])?;
+    let mut palette = match args.option("--from") {
+        Some(path) => import::import(Path::new(path))
+            .map_err(|error| CliError::Invalid(format!("{path}: {error}")))?,
+        None => Palette::new("Palette"),
+    };
+    for color in &args.positional {
+        palette.add(color_arg(color)?);
+    }
+    if palette.swatches.is_empty() {
+        return Err(CliError::Usage(
+            "export needs colors or a --from palette".into(),
+        ));
+    }
+    if let Some(name) = args.option("--name") {
+        palette.name = name.to_string();
+    }
+    let format = match args.option("--format") {
+        Some(name) => choose(name, "format", &ExportFormat::ALL, |format| {
+            vec![format.label(), format.extension()]
+        })?,
+        None => ExportFormat::Css,
+    };
+
+    let contents = format.export(&palette);
+    match args.option("--output") {
+        Some(path) => std::fs::write(PathBuf::from(path), contents)
+            .map_err(|error| CliError::Io(format!("{path}: {error}")))?,
+        None => out.write_all(&contents)?,
+    }
+    Ok(())
+}
+
+fn color_arg(text: &str) -> Result<Rgba, CliError> {
+    parse_color(text).map_err(|error| CliError::Invalid(format!("`{text}`: {error}")))
+}
+
+// Picks the choice one of whose names matches `name`, ignoring case
+fn choose<T: Copy>(
+    name: &str,
+    what: &str,
+    choices: &[T],
+    names: impl Fn(T) -> Vec<&'static str>,
+) -> Result<T, CliError> {
+    choices
+        .iter()
+        .copied()
+        .find(|&choice| {
+            names(choice)
+                .iter()
+                .any(|candidate| candidate.eq_ignore_ascii_case(name))
+        })
+        .ok_or_else(|| {
+            let known: Vec<String> = choices
+                .iter()
+                .map(|&choice| names(choice)[0].to_lowercase())
+                .collect();
+            CliError::Usage(format!(
+                "unknown {what} `{name}`, expected one of: {}",
+                known.join(", ")
+            ))
+        })
+}
+
+// Positional arguments plus `--name value` options, in any order
+struct Args {
+    positional: Vec<String>,
+    options: Vec<(String, String)>,
+}
+
+impl Args {
+    fn parse(args: &[String]) -> Result<Self, CliError> {
+        let mut positional = Vec::new();
+        let mut options = Vec::new();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            // No color notation starts with "--", so it always marks an option
+            if arg.starts_with("--") {
+                let value = args
+                    .next()
+                    .ok_or_else(|| CliError::Usage(format!("{arg} needs a value")))?;
+                options.push((arg.clone(), value.clone()));
+            } else {
+                positional.push(arg.clone());
+            }
+        }
+        Ok(Self {
+            positional,
+            options,
+        })
+    }
+
+    fn allow_options(&self, allowed: &[&str]) -> Result<(), CliError> {
+        match self
+            .options
+            .iter()
+            .find(|(name, _)| !allowed.contains(&name.as_str()))
+        {
+            Some((name, _)) => Err(CliError::Usage(format!("unknown option `{name}`"))),
+            None => Ok(()),
+        }
+    }
+
+    fn option(&self, name: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .rev()
+            .find(|(option, _)| option == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn positional<const N: usize>(
+        &self,
+        command: &str,
+        names: [&str; N],
+    ) -> Result<[&str; N], CliError> {
+        if self.positional.len() != N {
+            return Err(CliError::Usage(format!(
+                "{command} takes {}",
+                names.map(|name| format!("<{name}>")).join(" ")
+            )));
+        }
+        Ok(std::array::from_fn(|index| self.positional[index].as_str()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_ok(args: &[&str]) -> String {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        let mut out = Vec::new();
+        run(&args, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn run_err(args: &[&str]) -> CliError {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        run(&args, &mut Vec::new()).unwrap_err()
+    }
+
+    #[test]
+    fn converts_to_one_or_all_formats() {
+        assert_eq!(
+            run_ok(&["convert", "rebeccapurple", "--to", "rgb"]),
+            "rgb(102 51 153)\n"
+        );
+        let all = run_ok(&["convert", "#1976d2"]);
+        assert_eq!(all.lines().count(), ColorFormat::ALL.len());
+        assert!(all.starts_with("Hex        #1976d2\n"));
+    }
+
+    #[test]
+    fn reports_contrast_levels() {
+        let output = run_ok(&["contrast", "white", "#1976d2"]);
+        assert!(output.starts_with("Ratio  4.60:1\n"), "{output}");
+        assert!(output.contains("AA         pass\n"));
+        assert!(output.contains("AAA        fail\n"));
+        assert!(output.contains("APCA   Lc "));
+    }
+
+    #[test]
+    fn generates_harmonies() {
+        assert_eq!(
+            run_ok(&["harmony", "#ff0000", "--type", "triadic", "--space", "hsl"]),
+            "#ff0000\n#00ff00\n#0000ff\n"
+        );
+    }
+
+    #[test]
+    fn prints_scales() {
+        let css = run_ok(&["scale", "#1976d2", "--name", "brand"]);
+        assert!(css.contains("  --brand-500: #1976d2;\n"), "{css}");
+        let tokens = run_ok(&["scale", "#1976d2", "--format", "tokens", "--curve", "1.5"]);
+        assert!(tokens.contains("\"500\": { \"$type\": \"color\", \"$value\": \"#1976d2\" }"));
+        assert!(matches!(
+            run_err(&["scale", "red", "--curve", "9"]),
+            CliError::Usage(_)
+        ));
+    }
+
+    #[test]
+    fn exports_colors_and_writes_files() {
+        let css = run_ok(&["export", "red", "#00ff00", "--name", "Primaries"]);
+        assert!(css.contains("#ff0000"), "{css}");
+        assert!(css.contains("#00ff00"));
+
+        let path = std::env::temp_dir().join(format!("color-mixer-cli-{}.gpl", std::process::id()));
+        let path_arg = path.to_str().unwrap();
+        let output = run_ok(&["export", "blue", "--format", "gimp", "--output", path_arg]);
+        assert!(output.is_empty());
+        let written = import::import(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(written.swatches.len(), 1);
+    }
+
+    #[test]
+    fn rejects_bad_invocations() {
+        assert!(matches!(run_err(&[]), CliError::Usage(_)));
+        assert!(matches!(run_err(&["mix"]), CliError::Usage(_)));
+        assert!(matches!(run_err(&["convert"]), CliError::Usage(_)));
+        assert!(matches!(
+            run_err(&["convert", "red", "--to", "cmyk"]),
+            CliError::Usage(_)
+        ));
+        assert!(matches!(
+            run_err(&["convert", "red", "--bogus", "1"]),
+            CliError::Usage(_)
+        ));
+        assert!(matches!(
+            run_err(&["convert", "notacolor"]),
+            CliError::Invalid(_)
+        ));
+        assert!(matches!(run_err(&["export"]), CliError::Usage(_)));
+    }
+}
@@ -0,0 +1,528 @@
+use gpui::{Hsla, Rgba};
+use serde::{Deserialize, Serialize};
+
+// Upper bound for OKLCH chroma on the channel rows; sRGB never exceeds ~0.37
+const OKLCH_MAX_CHROMA: f32 = 0.4;
+
+/// Color space used by the channel rows of the mixer
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorMode {
+    #[default]
+    Rgb,
+    Hsl,
+    Hsv,
+    Oklch,
+}
+
+impl ColorMode {
+    pub const ALL: [ColorMode; 4] = [
+        ColorMode::Rgb,
+        ColorMode::Hsl,
+        ColorMode::Hsv,
+        ColorMode::Oklch,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ColorMode::Rgb => "RGB",
+            ColorMode::Hsl => "HSL",
+            ColorMode::Hsv => "HSV",
+            ColorMode::Oklch => "OKLCH",
+        }
+    }
+
+    pub fn channel_labels(self) -> [&'static str; 3] {
+        match self {
+            ColorMode::Rgb => ["Red", "Green", "Blue"],
+            ColorMode::Hsl => ["Hue", "Saturation", "Lightness"],
+            ColorMode::Hsv => ["Hue", "Saturation", "Value"],
+            ColorMode::Oklch => ["Lightness", "Chroma", "Hue"],
+        }
+    }
+
+    /// Formats a normalized channel value in the units designers expect
+    pub fn format_channel(self, index: usize, value: f32) -> String {
+        match (self, index) {
+            (ColorMode::Rgb, _) => format!("{}", (value * 255.0).round() as u8),
+            (ColorMode::Hsl | ColorMode::Hsv, 0) | (ColorMode::Oklch, 2) => {
+                format!("{:.0}°", value * 360.0)
+            }
+            (ColorMode::Oklch, 1) => format!("{:.3}", value * OKLCH_MAX_CHROMA),
+            _ => format!("{:.1}%", value * 100.0),
+        }
+    }
+
+    /// Keyboard step for a normalized channel: one unit of the displayed readout
+    pub fn step(self, index: usize) -> f32 {
+        match (self, index) {
+            (ColorMode::Rgb, _) => 1.0 / 255.0,
+            (ColorMode::Hsl | ColorMode::Hsv, 0) | (ColorMode::Oklch, 2) => 1.0 / 360.0,
+            (ColorMode::Oklch, 1) => 0.001 / OKLCH_MAX_CHROMA,
+            _ => 0.001,
+        }
+    }
+
+    /// Splits a color into this mode's three channels, each normalized to 0..1
+    pub fn decompose(self, color: Rgba) -> [f32; 3] {
+        match self {
+            ColorMode::Rgb => [color.r, color.g, color.b],
+            ColorMode::Hsl => {
+                let hsl = Hsla::from(color);
+                [hsl.h, hsl.s, hsl.l]
+            }
+            ColorMode::Hsv => {
+                let hsv = Hsv::from(color);
+                [hsv.h, hsv.s, hsv.v]
+            }
+            ColorMode::Oklch => {
+                let lch = Oklch::from(color);
+                [lch.l, lch.c / OKLCH_MAX_CHROMA, lch.h / 360.0]
+            }
+        }
+    }
+
+    /// Rebuilds a color from normalized channels, mapping it into the sRGB gamut
+    pub fn compose(self, [x, y, z]: [f32; 3], alpha: f32) -> Rgba {
+        let color = match self {
+            ColorMode::Rgb => Rgba {
+                r: x,
+                g: y,
+                b: z,
+                a: alpha,
+            },
+            ColorMode::Hsl => Rgba::from(Hsla {
+                h: x.clamp(0.0, 1.0),
+                s: y,
+                l: z,
+                a: alpha,
+            }),
+            ColorMode::Hsv => Hsv { h: x, s: y, v: z }.to_rgb(alpha),
+            ColorMode::Oklch => Oklch {
+                l: x,
+                c: y * OKLCH_MAX_CHROMA,
+                h: z * 360.0,
+            }
+            .to_rgb_clamped(alpha),
+        };
+        clamp_rgb(color)
+    }
+}
+
+/// Hue, saturation and value, all in 0..1
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Hsv {
+    pub h: f32,
+    pub s: f32,
+    pub v: f32,
+}
+
+impl From<Rgba> for Hsv {
+    fn from(color: Rgba) -> Self {
+        let max = color.r.max(color.g.max(color.b));
+        let min = color.r.min(color.g.min(color.b));
+        let delta = max - min;
+
+        let h = if delta == 0.0 {
+            0.0
+        } else if max == color.r {
+            ((color.g - color.b) / delta).rem_euclid(6.0) / 6.0
+        } else if max == color.g {
+            ((color.b - color.r) / delta + 2.0) / 6.0
+        } else {
+            ((color.r - color.g) / delta + 4.0) / 6.0
+        };
+        let s = if max == 0.0 { 0.0 } else { delta / max };
+
+        Self { h, s, v: max }
+    }
+}
+
+impl Hsv {
+    /// Takes the channels that carry no information for this color (the hue
+    /// of a gray, the saturation of black) from `previous`, so that a picker
+    /// keeps its position instead of snapping to zero
+    pub fn keep_undefined_from(self, previous: Hsv) -> Hsv {
+        Hsv {
+            h: if self.s == 0.0 || self.v == 0.0 {
+                previous.h
+            } else {
+                self.h
+            },
+            s: if self.v == 0.0 { previous.s } else { self.s },
+            v: self.v,
+        }
+    }
+
+    pub fn to_rgb(self, alpha: f32) -> Rgba {
+        let h = self.h.rem_euclid(1.0) * 6.0;
+        let s = self.s.clamp(0.0, 1.0);
+        let v = self.v.clamp(0.0, 1.0);
+
+        let c = v * s;
+        let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+        let m = v - c;
+        let (r, g, b) = match h as u32 {
+            0 => (c, x, 0.0),
+            1 => (x, c, 0.0),
+            2 => (0.0, c, x),
+            3 => (0.0, x, c),
+            4 => (x, 0.0, c),
+            _ => (c, 0.0, x),
+        };
+
+        Rgba {
+            r: r + m,
+            g: g + m,
+            b: b + m,
+            a: alpha,
+        }
+    }
+}
+
+/// Björn Ottosson's perceptual OKLab space
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Oklab {
+    pub l: f32,
+    pub a: f32,
+    pub b: f32,
+}
+
+// Matrix coefficients are kept verbatim from the reference implementation
+#[allow(clippy::excessive_precision)]
+impl From<Rgba> for Oklab {
+    fn from(color: Rgba) -> Self {
+        let r = srgb_to_linear(color.r);
+        let g = srgb_to_linear(color.g);
+        let b = srgb_to_linear(color.b);
+
+        let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+        let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+        let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+
+        Self {
+            l: 0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+            a: 1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+            b: 0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+        }
+    }
+}
+
+#[allow(clippy::excessive_precision)]
+impl Oklab {
+    /// Converts to sRGB without clamping; channels may fall outside 0..1
+    pub fn to_rgb_unclamped(self, alpha: f32) -> Rgba {
+        let l = (self.l + 0.3963377774 * self.a + 0.2158037573 * self.b).powi(3);
+        let m = (self.l - 0.1055613458 * self.a - 0.0638541728 * self.b).powi(3);
+        let s = (self.l - 0.0894841775 * self.a - 1.2914855480 * self.b).powi(3);
+
+        Rgba {
+            r: linear_to_srgb(4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s),
+            g: linear_to_srgb(-1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s),
+            b: linear_to_srgb(-0.0041960863 * l - 0.7034186147 * m + 1.7076147010 * s),
+            a: alpha,
+        }
+    }
+}
+
+/// Polar form of OKLab: lightness 0..1, chroma 0..~0.37, hue in degrees
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Oklch {
+    pub l: f32,
+    pub c: f32,
+    pub h: f32,
+}
+
+impl From<Rgba> for Oklch {
+    fn from(color: Rgba) -> Self {
+        Oklab::from(color).into()
+    }
+}
+
+impl From<Oklab> for Oklch {
+    fn from(lab: Oklab) -> Self {
+        let c = (lab.a * lab.a + lab.b * lab.b).sqrt();
+        // Achromatic colors have no meaningful hue
+        let h = if c < 1e-4 {
+            0.0
+        } else {
+            lab.b.atan2(lab.a).to_degrees().rem_euclid(360.0)
+        };
+        Self { l: lab.l, c, h }
+    }
+}
+
+impl From<Oklch> for Oklab {
+    fn from(lch: Oklch) -> Self {
+        let h = lch.h.to_radians();
+        Self {
+            l: lch.l,
+            a: lch.c * h.cos(),
+            b: lch.c * h.sin(),
+        }
+    }
+}
+
+impl Oklch {
+    /// Converts to sRGB, reducing chroma at constant lightness and hue until
+    /// the color fits the gamut (CSS Color 4 style gamut mapping)
+    pub fn to_rgb_clamped(self, alpha: f32) -> Rgba {
+        let l = self.l.clamp(0.0, 1.0);
+        let in_gamut = |c: f32| {
+            let rgb = Oklab::from(Oklch { l, c, h: self.h }).to_rgb_unclamped(alpha);
+            is_in_gamut(rgb).then_some(rgb)
+        };
+
+        if let Some(rgb) = in_gamut(self.c.max(0.0)) {
+            return rgb;
+        }
+
+        let (mut low, mut high) = (0.0, self.c);
+        for _ in 0..24 {
+            let mid = (low + high) / 2.0;
+            if in_gamut(mid).is_some() {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        clamp_rgb(
+            Oklab::from(Oklch {
+                l,
+                c: low,
+                h: self.h,
+            })
+            .to_rgb_unclamped(alpha),
+        )
+    }
+}
+
+/// CIELAB under the D65 white point: L 0..100, a/b roughly -128..127
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Lab {
+    pub l: f32,
+    pub a: f32,
+    pub b: f32,
+}
+
+const D65_WHITE: [f32; 3] = [0.95047, 1.0, 1.08883];
+const LAB_EPSILON: f32 = 6.0 / 29.0;
+
+// sRGB <-> XYZ matrices as published in the sRGB specification
+#[allow(clippy::excessive_precision)]
+impl From<Rgba> for Lab {
+    fn from(color: Rgba) -> Self {
+        let r = srgb_to_linear(color.r);
+        let g = srgb_to_linear(color.g);
+        let b = srgb_to_linear(color.b);
+
+        let x = 0.4124564 * r + 0.3575761 * g + 0.1804375 * b;
+        let y = 0.2126729 * r + 0.7151522 * g + 0.0721750 * b;
+        let z = 0.0193339 * r + 0.1191920 * g + 0.9503041 * b;
+
+        let f = |t: f32| {
+            if t > LAB_EPSILON.powi(3) {
+                t.cbrt()
+            } else {
+                t / (3.0 * LAB_EPSILON * LAB_EPSILON) + 4.0 / 29.0
+            }
+        };
+        let fx = f(x / D65_WHITE[0]);
+        let fy = f(y / D65_WHITE[1]);
+        let fz = f(z / D65_WHITE[2]);
+
+        Self {
+            l: 116.0 * fy - 16.0,
+            a: 500.0 * (fx - fy),
+            b: 200.0 * (fy - fz),
+        }
+    }
+}
+
+#[allow(clippy::excessive_precision)]
+impl Lab {
+    pub fn to_rgb(self, alpha: f32) -> Rgba {
+        let finv = |t: f32| {
+            if t > LAB_EPSILON {
+                t.powi(3)
+            } else {
+                3.0 * LAB_EPSILON * LAB_EPSILON * (t - 4.0 / 29.0)
+            }
+        };
+        let fy = (self.l + 16.0) / 116.0;
+        let x = D65_WHITE[0] * finv(fy + self.a / 500.0);
+        let y = D65_WHITE[1] * finv(fy);
+        let z = D65_WHITE[2] * finv(fy - self.b / 200.0);
+
+        clamp_rgb(Rgba {
+            r: linear_to_srgb(3.2404542 * x - 1.5371385 * y - 0.4985314 * z),
+            g: linear_to_srgb(-0.9692660 * x + 1.8760108 * y + 0.0415560 * z),
+            b: linear_to_srgb(0.0556434 * x - 0.2040259 * y + 1.0572252 * z),
+            a: alpha,
+        })
+    }
+}
+
+pub fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+pub fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.0031308 {
+        12.92 * c
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+fn is_in_gamut(color: Rgba) -> bool {
+    const EPSILON: f32 = 1e-4;
+    [color.r, color.g, color.b]
+        .iter()
+        .all(|c| (-EPSILON..=1.0 + EPSILON).contains(c))
+}
+
+/// Formats as `#rrggbb`, or `#rrggbbaa` when the color is translucent
+pub fn to_hex(color: Rgba) -> String {
+    let byte = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+    let hex = format!(
+        "#{:02x}{:02x}{:02x}",
+        byte(color.r),
+        byte(color.g),
+        byte(color.b)
+    );
+    if color.a < 1.0 {
+        format!("{hex}{:02x}", byte(color.a))
+    } else {
+        hex
+    }
+}
+
+pub fn clamp_rgb(color: Rgba) -> Rgba {
+    Rgba {
+        r: color.r.clamp(0.0, 1.0),
+        g: color.g.clamp(0.0, 1.0),
+        b: color.b.clamp(0.0, 1.0),
+        a: color.a.clamp(0.0, 1.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn color(r: f32, g: f32, b: f32) -> Rgba {
+        Rgba { r, g, b, a: 1.0 }
+    }
+
+    fn assert_close(actual: Rgba, expected: Rgba) {
+        let close = |x: f32, y: f32| (x - y).abs() < 1e-3;
+        assert!(
+            close(actual.r, expected.r)
+                && close(actual.g, expected.g)
+                && close(actual.b, expected.b),
+            "{actual:?} != {expected:?}"
+        );
+    }
+
+    fn samples() -> Vec<Rgba> {
+        let steps = [0.0, 0.2, 0.5, 0.8, 1.0];
+        let mut colors = Vec::new();
+        for r in steps {
+            for g in steps {
+                for b in steps {
+                    colors.push(color(r, g, b));
+                }
+            }
+        }
+        colors
+    }
+
+    #[test]
+    fn every_mode_round_trips() {
+        for mode in ColorMode::ALL {
+            for sample in samples() {
+                let channels = mode.decompose(sample);
+                assert_close(mode.compose(channels, 1.0), sample);
+            }
+        }
+    }
+
+    #[test]
+    fn lab_round_trips_and_matches_reference_values() {
+        for sample in samples() {
+            assert_close(Lab::from(sample).to_rgb(1.0), sample);
+        }
+
+        let red = Lab::from(color(1.0, 0.0, 0.0));
+        assert!((red.l - 53.24).abs() < 0.01);
+        assert!((red.a - 80.09).abs() < 0.01);
+        assert!((red.b - 67.20).abs() < 0.01);
+    }
+
+    #[test]
+    fn hsv_keeps_undefined_channels() {
+        let previous = Hsv {
+            h: 0.25,
+            s: 0.75,
+            v: 0.5,
+        };
+        let gray = Hsv::from(color(0.5, 0.5, 0.5)).keep_undefined_from(previous);
+        assert_eq!((gray.h, gray.s), (0.25, 0.0));
+        let black = Hsv::from(color(0.0, 0.0, 0.0)).keep_undefined_from(previous);
+        assert_eq!((black.h, black.s, black.v), (0.25, 0.75, 0.0));
+        let red = Hsv::from(color(1.0, 0.0, 0.0)).keep_undefined_from(previous);
+        assert_eq!((red.h, red.s, red.v), (0.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn hsv_matches_known_values() {
+        let hsv = Hsv::from(color(1.0, 0.5, 0.0));
+        assert!((hsv.h - 30.0 / 360.0).abs() < 1e-4);
+        assert_eq!(hsv.s, 1.0);
+        assert_eq!(hsv.v, 1.0);
+    }
+
+    #[test]
+    fn oklch_matches_reference_values() {
+        // Reference values from the CSS Color 4 specification
+        let white = Oklch::from(color(1.0, 1.0, 1.0));
+        assert!((white.l - 1.0).abs() < 1e-3 && white.c < 1e-3);
+
+        let red = Oklch::from(color(1.0, 0.0, 0.0));
+        assert!((red.l - 0.628).abs() < 1e-3);
+        assert!((red.c - 0.2577).abs() < 1e-3);
+        assert!((red.h - 29.23).abs() < 0.1);
+    }
+
+    #[test]
+    fn out_of_gamut_oklch_is_mapped_into_srgb() {
+        let vivid = Oklch {
+            l: 0.7,
+            c: 0.4,
+            h: 150.0,
+        };
+        let rgb = vivid.to_rgb_clamped(1.0);
+        assert!(is_in_gamut(rgb));
+
+        // Gamut mapping keeps lightness and hue, giving up chroma only
+        let mapped = Oklch::from(rgb);
+        assert!((mapped.l - 0.7).abs() < 0.01);
+        assert!((mapped.h - 150.0).abs() < 1.0);
+        assert!(mapped.c < 0.4);
+    }
+
+    #[test]
+    fn channels_are_clamped_to_gamut() {
+        let rgb = ColorMode::Rgb.compose([1.5, -0.2, 0.5], 1.0);
+        assert_close(rgb, color(1.0, 0.0, 0.5));
+
+        for mode in ColorMode::ALL {
+            assert!(is_in_gamut(mode.compose([1.0, 1.0, 1.0], 1.0)));
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use gpui::{
+    App, Context, EventEmitter, FocusHandle, Focusable, KeyDownEvent, Rgba, Window, div,
+    prelude::*, rgb,
+};
+
+use gpui_color_mixer::parse::{ParseColorError, parse_color};
+
+/// Emitted when the user presses enter on a valid color
+pub struct ColorCommitted(pub Rgba);
+
+/// Single-line text field that accepts any color `parse_color` understands
+pub struct ColorInput {
+    focus_handle: FocusHandle,
+    text: String,
+    // Text to restore on escape, i.e. the last synced or committed value
+    committed_text: String,
+    editing: bool,
+    error: Option<ParseColorError>,
+}
+
+impl EventEmitter<ColorCommitted> for ColorInput {}
+
+impl Focusable for ColorInput {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl ColorInput {
+    pub fn new(text: String, cx: &mut Context<Self>) -> Self {
+        Self {
+            focus_handle: cx.focus_handle().tab_stop(true),
+            committed_text: text.clone(),
+            text,
+            editing: false,
+            error: None,
+        }
+    }
+
+    /// Shows the given text unless the user is in the middle of typing
+    pub fn set_text(&mut self, text: String, cx: &mut Context<Self>) {
+        self.committed_text = text;
+        if !self.editing {
+            self.text = self.committed_text.clone();
+            self.error = None;
+            cx.notify();
+        }
+    }
+
+    fn edit(&mut self, text: String, cx: &mut Context<Self>) {
+        self.text = text;
+        self.editing = true;
+        self.error = None;
+        cx.notify();
+    }
+
+    fn commit(&mut self, cx: &mut Context<Self>) {
+        match parse_color(&self.text) {
+            Ok(color) => {
+                self.editing = false;
+                self.error = None;
+                cx.emit(ColorCommitted(color));
+            }
+            Err(error) => self.error = Some(error),
+        }
+        cx.notify();
+    }
+
+    fn revert(&mut self, cx: &mut Context<Self>) {
+        self.editing = false;
+        self.text = self.committed_text.clone();
+        self.error = None;
+        cx.notify();
+    }
+
+    fn handle_key_down(&mut self, event: &KeyDownEvent, cx: &mut Context<Self>) {
+        let keystroke = &event.keystroke;
+        let secondary = keystroke.modifiers.secondary();
+
+        match keystroke.key.as_str() {
+            "enter" => self.commit(cx),
+            "escape" => self.revert(cx),
+            "backspace" if secondary => self.edit(String::new(), cx),
+            "backspace" => {
+                let mut text = self.text.clone();
+                text.pop();
+                self.edit(text, cx);
+            }
+            "v" if secondary => {
+                if let Some(pasted) = cx.read_from_clipboard().and_then(|item| item.text()) {
+                    let line = pasted.lines().next().unwrap_or_default().trim();
+                    self.edit(format!("{}{line}", self.text), cx);
+                }
+            }
+            _ if !secondary && !keystroke.modifiers.control => {
+                if let Some(typed) = &keystroke.key_char {
+                    self.edit(format!("{}{typed}", self.text), cx);
+                }
+            }
+            _ => return,
+        }
+        cx.stop_propagation();
+    }
+}
+
+impl Render for ColorInput {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let focused = self.focus_handle.is_focused(window);
+        let valid = parse_color(&self.text).is_ok();
+        let border = if self.error.is_some() || (self.editing && !valid) {
+            rgb(0xe57373)
+        } else if focused {
+            rgb(0x64b5f6)
+        } else {
+            rgb(0x505050)
+        };
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_1()
+            .child(
+                div()
+                    .id("color-input")
+                    .track_focus(&self.focus_handle)
+                    .on_key_down(cx.listener(|this, event, _, cx| {
+                        this.handle_key_down(event, cx);
+                    }))
+                    .px_2()
+                    .py_1()
+                    .rounded_md()
+                    .bg(rgb(0x202020))
+                    .border_1()
+                    .border_color(border)
+                    .cursor_text()
+                    .child(if focused {
+                        format!("{}|", self.text)
+                    } else {
+                        self.text.clone()
+                    }),
+            )
+            .children(self.error.as_ref().map(|error| {
+                div()
+                    .text_sm()
+                    .text_color(rgb(0xe57373))
+                    .child(error.to_string())
+            }))
+    }
+}
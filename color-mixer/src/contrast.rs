@@ -0,0 +1,297 @@
+use gpui::Rgba;
+
+use crate::color::{Oklch, srgb_to_linear};
+
+/// WCAG 2.1 relative luminance of an opaque sRGB color
+pub fn relative_luminance(color: Rgba) -> f32 {
+    0.2126 * srgb_to_linear(color.r)
+        + 0.7152 * srgb_to_linear(color.g)
+        + 0.0722 * srgb_to_linear(color.b)
+}
+
+/// Paints `foreground` over `background`. The background's own alpha is
+/// ignored since contrast is only defined against an opaque backdrop.
+pub fn composite(foreground: Rgba, background: Rgba) -> Rgba {
+    let a = foreground.a;
+    Rgba {
+        r: foreground.r * a + background.r * (1.0 - a),
+        g: foreground.g * a + background.g * (1.0 - a),
+        b: foreground.b * a + background.b * (1.0 - a),
+        a: 1.0,
+    }
+}
+
+/// WCAG 2.1 contrast ratio, from 1.0 up to 21.0
+pub fn contrast_ratio(foreground: Rgba, background: Rgba) -> f32 {
+    let background = Rgba {
+        a: 1.0,
+        ..background
+    };
+    let foreground = relative_luminance(composite(foreground, background));
+    let background = relative_luminance(background);
+    let (lighter, darker) = if foreground > background {
+        (foreground, background)
+    } else {
+        (background, foreground)
+    };
+    (lighter + 0.05) / (darker + 0.05)
+}
+
+/// APCA lightness contrast (Lc) of text on a background, per APCA-W3 0.0.98G.
+/// Positive for dark text on light backgrounds, negative for light on dark.
+pub fn apca_contrast(text: Rgba, background: Rgba) -> f32 {
+    const BLACK_THRESHOLD: f32 = 0.022;
+    const BLACK_CLAMP: f32 = 1.414;
+    const SCALE: f32 = 1.14;
+    const OFFSET: f32 = 0.027;
+    const LOW_CLIP: f32 = 0.1;
+    const DELTA_Y_MIN: f32 = 0.0005;
+
+    // APCA uses a plain 2.4 exponent rather than the piecewise sRGB curve
+    let luminance = |color: Rgba| {
+        let y = 0.2126729 * color.r.powf(2.4)
+            + 0.7151522 * color.g.powf(2.4)
+            + 0.0721750 * color.b.powf(2.4);
+        if y < BLACK_THRESHOLD {
+            y + (BLACK_THRESHOLD - y).powf(BLACK_CLAMP)
+        } else {
+            y
+        }
+    };
+
+    let background = Rgba {
+        a: 1.0,
+        ..background
+    };
+    let text_y = luminance(composite(text, background));
+    let background_y = luminance(background);
+    if (background_y - text_y).abs() < DELTA_Y_MIN {
+        return 0.0;
+    }
+
+    let contrast = if background_y > text_y {
+        let sapc = (background_y.powf(0.56) - text_y.powf(0.57)) * SCALE;
+        if sapc < LOW_CLIP { 0.0 } else { sapc - OFFSET }
+    } else {
+        let sapc = (background_y.powf(0.65) - text_y.powf(0.62)) * SCALE;
+        if sapc > -LOW_CLIP { 0.0 } else { sapc + OFFSET }
+    };
+    contrast * 100.0
+}
+
+/// Which side of the contrast pair the mixer's current color plays
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ContrastRole {
+    Text,
+    #[default]
+    Background,
+}
+
+impl ContrastRole {
+    pub const ALL: [ContrastRole; 2] = [ContrastRole::Text, ContrastRole::Background];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ContrastRole::Text => "Text",
+            ContrastRole::Background => "Background",
+        }
+    }
+}
+
+/// A pass/fail level a text and background pair can be checked against
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ContrastTarget {
+    AaLarge,
+    #[default]
+    Aa,
+    AaaLarge,
+    Aaa,
+    // APCA suggestions: large/bold text, body text, and preferred body text
+    Lc60,
+    Lc75,
+    Lc90,
+}
+
+impl ContrastTarget {
+    pub const ALL: [ContrastTarget; 7] = [
+        ContrastTarget::AaLarge,
+        ContrastTarget::Aa,
+        ContrastTarget::AaaLarge,
+        ContrastTarget::Aaa,
+        ContrastTarget::Lc60,
+        ContrastTarget::Lc75,
+        ContrastTarget::Lc90,
+    ];
+
+    // The four WCAG levels, shown as badges
+    pub const WCAG: [ContrastTarget; 4] = [
+        ContrastTarget::Aa,
+        ContrastTarget::AaLarge,
+        ContrastTarget::Aaa,
+        ContrastTarget::AaaLarge,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ContrastTarget::AaLarge => "AA Large",
+            ContrastTarget::Aa => "AA",
+            ContrastTarget::AaaLarge => "AAA Large",
+            ContrastTarget::Aaa => "AAA",
+            ContrastTarget::Lc60 => "Lc 60",
+            ContrastTarget::Lc75 => "Lc 75",
+            ContrastTarget::Lc90 => "Lc 90",
+        }
+    }
+
+    pub fn is_met(self, text: Rgba, background: Rgba) -> bool {
+        let ratio = || contrast_ratio(text, background);
+        let lc = || apca_contrast(text, background).abs();
+        match self {
+            ContrastTarget::AaLarge => ratio() >= 3.0,
+            ContrastTarget::Aa | ContrastTarget::AaaLarge => ratio() >= 4.5,
+            ContrastTarget::Aaa => ratio() >= 7.0,
+            ContrastTarget::Lc60 => lc() >= 60.0,
+            ContrastTarget::Lc75 => lc() >= 75.0,
+            ContrastTarget::Lc90 => lc() >= 90.0,
+        }
+    }
+}
+
+/// Finds the color closest in OKLCH lightness to `color` that satisfies
+/// `passes`, keeping its hue, chroma (where the gamut allows) and alpha.
+/// Returns `None` when neither black nor white end of the range passes.
+pub fn nudge_lightness(color: Rgba, passes: impl Fn(Rgba) -> bool) -> Option<Rgba> {
+    const STEP: f32 = 0.002;
+
+    if passes(color) {
+        return Some(color);
+    }
+    let lch = Oklch::from(color);
+    let with_lightness = |l: f32| Oklch { l, ..lch }.to_rgb_clamped(color.a);
+
+    let steps = (1.0 / STEP).ceil() as usize;
+    (1..=steps).find_map(|step| {
+        let delta = step as f32 * STEP;
+        [lch.l - delta, lch.l + delta]
+            .into_iter()
+            .filter(|l| (0.0..=1.0).contains(l))
+            .map(with_lightness)
+            .find(|candidate| passes(*candidate))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::{rgb, rgba};
+
+    use super::*;
+
+    fn close(actual: f32, expected: f32, tolerance: f32) -> bool {
+        (actual - expected).abs() <= tolerance
+    }
+
+    #[test]
+    fn wcag_ratio_matches_reference_values() {
+        assert!(close(
+            contrast_ratio(rgb(0x000000), rgb(0xffffff)),
+            21.0,
+            0.01
+        ));
+        assert!(close(
+            contrast_ratio(rgb(0xffffff), rgb(0xffffff)),
+            1.0,
+            0.001
+        ));
+        // Symmetric in its arguments
+        assert!(close(
+            contrast_ratio(rgb(0x767676), rgb(0xffffff)),
+            4.54,
+            0.01
+        ));
+        assert!(close(
+            contrast_ratio(rgb(0xffffff), rgb(0x767676)),
+            4.54,
+            0.01
+        ));
+    }
+
+    #[test]
+    fn translucent_text_is_composited_first() {
+        let half_black = rgba(0x00000080);
+        let gray = composite(half_black, rgb(0xffffff));
+        assert!(close(
+            contrast_ratio(half_black, rgb(0xffffff)),
+            contrast_ratio(gray, rgb(0xffffff)),
+            0.001
+        ));
+    }
+
+    #[test]
+    fn apca_matches_reference_values() {
+        assert!(close(
+            apca_contrast(rgb(0x000000), rgb(0xffffff)),
+            106.04,
+            0.1
+        ));
+        assert!(close(
+            apca_contrast(rgb(0xffffff), rgb(0x000000)),
+            -107.88,
+            0.1
+        ));
+        assert!(close(
+            apca_contrast(rgb(0x888888), rgb(0xffffff)),
+            63.06,
+            0.1
+        ));
+        assert!(close(
+            apca_contrast(rgb(0xffffff), rgb(0x888888)),
+            -68.54,
+            0.1
+        ));
+        assert_eq!(apca_contrast(rgb(0x808080), rgb(0x808080)), 0.0);
+    }
+
+    #[test]
+    fn targets_use_the_right_thresholds() {
+        let (text, background) = (rgb(0x767676), rgb(0xffffff));
+        assert!(ContrastTarget::AaLarge.is_met(text, background));
+        assert!(ContrastTarget::Aa.is_met(text, background));
+        assert!(!ContrastTarget::Aaa.is_met(text, background));
+        assert!(!ContrastTarget::Lc75.is_met(text, background));
+    }
+
+    #[test]
+    fn nudge_makes_the_smallest_passing_change() {
+        let background = rgb(0xffffff);
+        for target in ContrastTarget::ALL {
+            let passes = |color| target.is_met(color, background);
+            let text = rgb(0x8899aa);
+            let nudged = nudge_lightness(text, passes).unwrap();
+            assert!(passes(nudged), "{target:?}");
+            // Text on white can only pass by getting darker, and not by much more than needed
+            let (before, after) = (Oklch::from(text), Oklch::from(nudged));
+            assert!(after.l < before.l, "{target:?}");
+            let slightly_lighter = Oklch {
+                l: after.l + 0.01,
+                ..after
+            }
+            .to_rgb_clamped(1.0);
+            assert!(!passes(slightly_lighter), "{target:?}");
+            assert!((after.h - before.h).abs() < 2.0, "{target:?}");
+        }
+    }
+
+    #[test]
+    fn nudge_leaves_passing_colors_alone_and_gives_up_when_impossible() {
+        let background = rgb(0xffffff);
+        let black = rgb(0x000000);
+        assert_eq!(
+            nudge_lightness(black, |c| ContrastTarget::Aaa.is_met(c, background)),
+            Some(black)
+        );
+        assert_eq!(
+            nudge_lightness(rgb(0x808080), |c| contrast_ratio(c, background) > 22.0),
+            None
+        );
+    }
+}
@@ -0,0 +1,186 @@
+use std::f64::consts::PI;
+
+use gpui::{Rgba, rgb};
+
+use crate::color::Lab;
+use crate::named_colors::NAMED_COLORS;
+use crate::palette::Palette;
+use crate::theme_colors::THEME_COLORS;
+
+/// CIEDE2000 color difference. Around 1.0 is the smallest difference most
+/// people notice side by side; below 2.0 colors read as the same.
+///
+/// Follows Sharma, Wu and Dalal, "The CIEDE2000 Color-Difference Formula:
+/// Implementation Notes" (2005), with all weighting factors at 1.
+pub fn delta_e_2000(first: Lab, second: Lab) -> f32 {
+    let (l1, a1, b1) = (first.l as f64, first.a as f64, first.b as f64);
+    let (l2, a2, b2) = (second.l as f64, second.a as f64, second.b as f64);
+
+    let c_bar = (a1.hypot(b1) + a2.hypot(b2)) / 2.0;
+    let g = 0.5 * (1.0 - (c_bar.powi(7) / (c_bar.powi(7) + 25f64.powi(7))).sqrt());
+    let a1 = a1 * (1.0 + g);
+    let a2 = a2 * (1.0 + g);
+    let c1 = a1.hypot(b1);
+    let c2 = a2.hypot(b2);
+    // Hue angle in degrees, 0 for achromatic colors
+    let hue = |a: f64, b: f64| {
+        if a == 0.0 && b == 0.0 {
+            0.0
+        } else {
+            b.atan2(a).to_degrees().rem_euclid(360.0)
+        }
+    };
+    let h1 = hue(a1, b1);
+    let h2 = hue(a2, b2);
+
+    let delta_l = l2 - l1;
+    let delta_c = c2 - c1;
+    let delta_h = if c1 * c2 == 0.0 {
+        0.0
+    } else if (h2 - h1).abs() <= 180.0 {
+        h2 - h1
+    } else if h2 - h1 > 180.0 {
+        h2 - h1 - 360.0
+    } else {
+        h2 - h1 + 360.0
+    };
+    let delta_h = 2.0 * (c1 * c2).sqrt() * (delta_h.to_radians() / 2.0).sin();
+
+    let l_bar = (l1 + l2) / 2.0;
+    let c_bar = (c1 + c2) / 2.0;
+    let h_bar = if c1 * c2 == 0.0 {
+        h1 + h2
+    } else if (h1 - h2).abs() <= 180.0 {
+        (h1 + h2) / 2.0
+    } else if h1 + h2 < 360.0 {
+        (h1 + h2 + 360.0) / 2.0
+    } else {
+        (h1 + h2 - 360.0) / 2.0
+    };
+
+    let t = 1.0 - 0.17 * (h_bar - 30.0).to_radians().cos()
+        + 0.24 * (2.0 * h_bar).to_radians().cos()
+        + 0.32 * (3.0 * h_bar + 6.0).to_radians().cos()
+        - 0.20 * (4.0 * h_bar - 63.0).to_radians().cos();
+    let delta_theta = 30.0 * (-((h_bar - 275.0) / 25.0).powi(2)).exp();
+    let r_c = 2.0 * (c_bar.powi(7) / (c_bar.powi(7) + 25f64.powi(7))).sqrt();
+    let s_l = 1.0 + 0.015 * (l_bar - 50.0).powi(2) / (20.0 + (l_bar - 50.0).powi(2)).sqrt();
+    let s_c = 1.0 + 0.045 * c_bar;
+    let s_h = 1.0 + 0.015 * c_bar * t;
+    let r_t = -(2.0 * delta_theta * PI / 180.0).sin() * r_c;
+
+    let l_term = delta_l / s_l;
+    let c_term = delta_c / s_c;
+    let h_term = delta_h / s_h;
+    (l_term * l_term + c_term * c_term + h_term * h_term + r_t * c_term * h_term).sqrt() as f32
+}
+
+/// The CSS named color closest to `color`, ignoring alpha, and its distance
+pub fn nearest_named(color: Rgba) -> (&'static str, f32) {
+    let lab = Lab::from(color);
+    NAMED_COLORS
+        .iter()
+        .map(|&(name, hex)| (name, delta_e_2000(lab, Lab::from(rgb(hex)))))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .expect("there are named colors")
+}
+
+/// Index of the palette swatch closest to `color`, ignoring alpha, and its
+/// distance; `None` for an empty palette
+pub fn nearest_swatch(palette: &Palette, color: Rgba) -> Option<(usize, f32)> {
+    let lab = Lab::from(color);
+    palette
+        .swatches
+        .iter()
+        .map(|swatch| delta_e_2000(lab, Lab::from(swatch.color)))
+        .enumerate()
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+/// Index into `THEME_COLORS` of the theme token closest to `color`, ignoring
+/// alpha, and its distance
+pub fn nearest_theme_color(color: Rgba) -> (usize, f32) {
+    let lab = Lab::from(color);
+    THEME_COLORS
+        .iter()
+        .map(|&(_, hex)| delta_e_2000(lab, Lab::from(rgb(hex))))
+        .enumerate()
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .expect("there are theme colors")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lab(l: f32, a: f32, b: f32) -> Lab {
+        Lab { l, a, b }
+    }
+
+    #[test]
+    fn matches_published_test_data() {
+        // Pairs 1, 7, 17, 25 and 34 from Sharma et al.'s table
+        let pairs = [
+            (
+                lab(50.0, 2.6772, -79.7751),
+                lab(50.0, 0.0, -82.7485),
+                2.0425,
+            ),
+            (lab(50.0, 0.0, 0.0), lab(50.0, -1.0, 2.0), 2.3669),
+            (lab(50.0, 2.5, 0.0), lab(73.0, 25.0, -18.0), 27.1492),
+            (
+                lab(60.2574, -34.0099, 36.2677),
+                lab(60.4626, -34.1751, 39.4387),
+                1.2644,
+            ),
+            (
+                lab(2.0776, 0.0795, -1.1350),
+                lab(0.9033, -0.0636, -0.5514),
+                0.9082,
+            ),
+        ];
+        for (first, second, expected) in pairs {
+            let distance = delta_e_2000(first, second);
+            assert!(
+                (distance - expected).abs() < 1e-3,
+                "{distance} != {expected}"
+            );
+            assert!((delta_e_2000(second, first) - expected).abs() < 1e-3);
+        }
+        assert_eq!(
+            delta_e_2000(lab(40.0, 10.0, 10.0), lab(40.0, 10.0, 10.0)),
+            0.0
+        );
+    }
+
+    #[test]
+    fn finds_the_nearest_named_color() {
+        assert_eq!(nearest_named(rgb(0x663399)), ("rebeccapurple", 0.0));
+        let (name, distance) = nearest_named(rgb(0xfe0102));
+        assert_eq!(name, "red");
+        assert!(distance < 1.0);
+    }
+
+    #[test]
+    fn finds_the_nearest_swatch() {
+        let mut palette = Palette::new("Test");
+        assert_eq!(nearest_swatch(&palette, rgb(0x000000)), None);
+
+        palette.add(rgb(0xffffff));
+        palette.add(rgb(0x1976d2));
+        palette.add(rgb(0x000000));
+        let (index, distance) = nearest_swatch(&palette, rgb(0x1a77d0)).unwrap();
+        assert_eq!(index, 1);
+        assert!(distance > 0.0 && distance < 1.0);
+    }
+
+    #[test]
+    fn finds_the_nearest_theme_color() {
+        let (index, distance) = nearest_theme_color(rgb(0x1976d2));
+        assert_eq!(THEME_COLORS[index].0, "light.accent_primary");
+        assert_eq!(distance, 0.0);
+        let (index, distance) = nearest_theme_color(rgb(0xe57070));
+        assert_eq!(THEME_COLORS[index].0, "dark.error");
+        assert!(distance < 2.0);
+    }
+}
@@ -0,0 +1,199 @@
+use gpui::{Hsla, Rgba};
+
+use crate::color::{Hsv, Lab, Oklch, to_hex};
+
+/// Text notations a color can be written out in
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorFormat {
+    #[default]
+    Hex,
+    Rgb,
+    Hsl,
+    Hsv,
+    Oklch,
+    Lab,
+    // gpui source: `rgb(0x1976d2)` and `Rgba { .. }` literals
+    RustHex,
+    RustRgba,
+}
+
+impl ColorFormat {
+    pub const ALL: [ColorFormat; 8] = [
+        ColorFormat::Hex,
+        ColorFormat::Rgb,
+        ColorFormat::Hsl,
+        ColorFormat::Hsv,
+        ColorFormat::Oklch,
+        ColorFormat::Lab,
+        ColorFormat::RustHex,
+        ColorFormat::RustRgba,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ColorFormat::Hex => "Hex",
+            ColorFormat::Rgb => "RGB",
+            ColorFormat::Hsl => "HSL",
+            ColorFormat::Hsv => "HSV",
+            ColorFormat::Oklch => "OKLCH",
+            ColorFormat::Lab => "Lab",
+            ColorFormat::RustHex => "Rust rgb()",
+            ColorFormat::RustRgba => "Rust Rgba",
+        }
+    }
+
+    /// Short lowercase name used on the command line
+    pub fn id(self) -> &'static str {
+        match self {
+            ColorFormat::Hex => "hex",
+            ColorFormat::Rgb => "rgb",
+            ColorFormat::Hsl => "hsl",
+            ColorFormat::Hsv => "hsv",
+            ColorFormat::Oklch => "oklch",
+            ColorFormat::Lab => "lab",
+            ColorFormat::RustHex => "rust",
+            ColorFormat::RustRgba => "rust-rgba",
+        }
+    }
+
+    /// CSS Color 4 syntax where CSS has one; HSV, which it lacks, uses the
+    /// same shape. The Rust formats paste straight into gpui code.
+    pub fn format(self, color: Rgba) -> String {
+        let alpha = if color.a < 1.0 {
+            format!(" / {}", trim(color.a, 3))
+        } else {
+            String::new()
+        };
+        match self {
+            ColorFormat::Hex => to_hex(color),
+            ColorFormat::Rgb => {
+                let [r, g, b] =
+                    [color.r, color.g, color.b].map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+                format!("rgb({r} {g} {b}{alpha})")
+            }
+            ColorFormat::Hsl => {
+                let hsl = Hsla::from(color);
+                format!(
+                    "hsl({} {}% {}%{alpha})",
+                    trim(hsl.h * 360.0, 1),
+                    trim(hsl.s * 100.0, 1),
+                    trim(hsl.l * 100.0, 1)
+                )
+            }
+            ColorFormat::Hsv => {
+                let hsv = Hsv::from(color);
+                format!(
+                    "hsv({} {}% {}%{alpha})",
+                    trim(hsv.h * 360.0, 1),
+                    trim(hsv.s * 100.0, 1),
+                    trim(hsv.v * 100.0, 1)
+                )
+            }
+            ColorFormat::Oklch => {
+                let lch = Oklch::from(color);
+                format!(
+                    "oklch({}% {} {}{alpha})",
+                    trim(lch.l * 100.0, 1),
+                    trim(lch.c, 3),
+                    trim(lch.h, 1)
+                )
+            }
+            ColorFormat::Lab => {
+                let lab = Lab::from(color);
+                format!(
+                    "lab({}% {} {}{alpha})",
+                    trim(lab.l, 1),
+                    trim(lab.a, 1),
+                    trim(lab.b, 1)
+                )
+            }
+            ColorFormat::RustHex => {
+                // `to_hex` only adds the alpha byte for translucent colors
+                let function = if color.a < 1.0 { "rgba" } else { "rgb" };
+                format!("{function}(0x{})", &to_hex(color)[1..])
+            }
+            ColorFormat::RustRgba => {
+                let [r, g, b, a] =
+                    [color.r, color.g, color.b, color.a].map(|c| float_literal(c.clamp(0.0, 1.0)));
+                format!("Rgba {{ r: {r}, g: {g}, b: {b}, a: {a} }}")
+            }
+        }
+    }
+}
+
+// Rounds to `decimals` places and drops trailing zeros, so 50.0 prints as "50"
+fn trim(value: f32, decimals: usize) -> String {
+    let text = format!("{value:.decimals$}");
+    let text = if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &text
+    };
+    // Avoid "-0" from tiny negative rounding errors
+    if text == "-0" { "0" } else { text }.to_string()
+}
+
+// A float that is valid Rust source, so 1 prints as "1.0"
+fn float_literal(value: f32) -> String {
+    let text = trim(value, 3);
+    if text.contains('.') {
+        text
+    } else {
+        format!("{text}.0")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::{rgb, rgba};
+
+    use super::*;
+    use crate::parse::parse_color;
+
+    #[test]
+    fn formats_every_notation() {
+        let color = rgb(0x1976d2);
+        let formatted: Vec<_> = ColorFormat::ALL
+            .iter()
+            .map(|format| format.format(color))
+            .collect();
+        assert_eq!(
+            formatted,
+            [
+                "#1976d2",
+                "rgb(25 118 210)",
+                "hsl(209.8 78.7% 46.1%)",
+                "hsv(209.8 88.1% 82.4%)",
+                "oklch(56.5% 0.163 253.3)",
+                "lab(49.3% 8.5 -54.5)",
+                "rgb(0x1976d2)",
+                "Rgba { r: 0.098, g: 0.463, b: 0.824, a: 1.0 }",
+            ]
+        );
+    }
+
+    #[test]
+    fn translucent_colors_get_an_alpha_term() {
+        assert_eq!(
+            ColorFormat::Rgb.format(rgba(0xff000080)),
+            "rgb(255 0 0 / 0.502)"
+        );
+        assert_eq!(
+            ColorFormat::Hsl.format(rgba(0xffffff00)),
+            "hsl(0 0% 100% / 0)"
+        );
+        assert_eq!(
+            ColorFormat::RustHex.format(rgba(0xff000080)),
+            "rgba(0xff000080)"
+        );
+    }
+
+    #[test]
+    fn css_notations_parse_back() {
+        let color = rgb(0x4caf50);
+        for format in [ColorFormat::Hex, ColorFormat::Rgb, ColorFormat::Hsl] {
+            let parsed = parse_color(&format.format(color)).unwrap();
+            assert_eq!(to_hex(parsed), "#4caf50", "{format:?}");
+        }
+    }
+}
@@ -0,0 +1,342 @@
+use std::fmt::Write;
+
+use gpui::Rgba;
+use serde::Serialize;
+
+use crate::color::{Oklab, clamp_rgb, linear_to_srgb, srgb_to_linear, to_hex};
+use crate::palette::Palette;
+
+/// Color space in which a gradient blends between neighboring stops
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Interpolation {
+    Srgb,
+    Linear,
+    #[default]
+    Oklab,
+}
+
+impl Interpolation {
+    pub const ALL: [Interpolation; 3] = [
+        Interpolation::Srgb,
+        Interpolation::Linear,
+        Interpolation::Oklab,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Interpolation::Srgb => "sRGB",
+            Interpolation::Linear => "Linear",
+            Interpolation::Oklab => "OKLab",
+        }
+    }
+
+    // Name of the space in CSS Color 4 `in <space>` syntax
+    fn css_name(self) -> &'static str {
+        match self {
+            Interpolation::Srgb => "srgb",
+            Interpolation::Linear => "srgb-linear",
+            Interpolation::Oklab => "oklab",
+        }
+    }
+
+    fn decompose(self, color: Rgba) -> [f32; 3] {
+        match self {
+            Interpolation::Srgb => [color.r, color.g, color.b],
+            Interpolation::Linear => [color.r, color.g, color.b].map(srgb_to_linear),
+            Interpolation::Oklab => {
+                let lab = Oklab::from(color);
+                [lab.l, lab.a, lab.b]
+            }
+        }
+    }
+
+    fn compose(self, [x, y, z]: [f32; 3], alpha: f32) -> Rgba {
+        let color = match self {
+            Interpolation::Srgb => Rgba {
+                r: x,
+                g: y,
+                b: z,
+                a: alpha,
+            },
+            Interpolation::Linear => Rgba {
+                r: linear_to_srgb(x),
+                g: linear_to_srgb(y),
+                b: linear_to_srgb(z),
+                a: alpha,
+            },
+            Interpolation::Oklab => Oklab { l: x, a: y, b: z }.to_rgb_unclamped(alpha),
+        };
+        clamp_rgb(color)
+    }
+
+    /// Blends `from` towards `to` by `t`, with premultiplied alpha as CSS does
+    pub fn mix(self, from: Rgba, to: Rgba, t: f32) -> Rgba {
+        let alpha = from.a + (to.a - from.a) * t;
+        let [from_space, to_space] =
+            [from, to].map(|color| self.decompose(color).map(|channel| channel * color.a));
+        let mut mixed = [0.0; 3];
+        for (channel, (a, b)) in mixed.iter_mut().zip(from_space.into_iter().zip(to_space)) {
+            let premultiplied = a + (b - a) * t;
+            *channel = if alpha > 0.0 {
+                premultiplied / alpha
+            } else {
+                0.0
+            };
+        }
+        self.compose(mixed, alpha)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GradientStop {
+    // 0..1 along the gradient line
+    pub position: f32,
+    pub color: Rgba,
+}
+
+/// A left-to-right gradient of two or more stops. Stops are kept in the order
+/// they were added; sampling and export sort them by position.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gradient {
+    pub stops: Vec<GradientStop>,
+    pub interpolation: Interpolation,
+}
+
+impl Gradient {
+    pub fn new(from: Rgba, to: Rgba) -> Self {
+        Self {
+            stops: vec![
+                GradientStop {
+                    position: 0.0,
+                    color: from,
+                },
+                GradientStop {
+                    position: 1.0,
+                    color: to,
+                },
+            ],
+            interpolation: Interpolation::default(),
+        }
+    }
+
+    fn sorted_stops(&self) -> Vec<GradientStop> {
+        let mut stops = self.stops.clone();
+        stops.sort_by(|a, b| a.position.total_cmp(&b.position));
+        stops
+    }
+
+    /// Color at `t` in 0..1; before the first and after the last stop the
+    /// gradient holds that stop's color
+    pub fn sample(&self, t: f32) -> Rgba {
+        let stops = self.sorted_stops();
+        let (first, last) = (stops[0], stops[stops.len() - 1]);
+        if t <= first.position {
+            return first.color;
+        }
+        if t >= last.position {
+            return last.color;
+        }
+
+        let index = stops.iter().position(|stop| stop.position > t).unwrap();
+        let (from, to) = (stops[index - 1], stops[index]);
+        let span = to.position - from.position;
+        let local = if span > 0.0 {
+            (t - from.position) / span
+        } else {
+            1.0
+        };
+        self.interpolation.mix(from.color, to.color, local)
+    }
+
+    /// `count` evenly spaced samples including both ends
+    pub fn steps(&self, count: usize) -> Vec<Rgba> {
+        match count {
+            0 => Vec::new(),
+            1 => vec![self.sample(0.5)],
+            _ => (0..count)
+                .map(|index| self.sample(index as f32 / (count - 1) as f32))
+                .collect(),
+        }
+    }
+
+    /// Inserts a stop in the middle of the widest gap, colored so the
+    /// gradient looks unchanged, and returns its index
+    pub fn add_stop(&mut self) -> usize {
+        let stops = self.sorted_stops();
+        let (start, end) = stops
+            .windows(2)
+            .map(|pair| (pair[0].position, pair[1].position))
+            .max_by(|a, b| (a.1 - a.0).total_cmp(&(b.1 - b.0)))
+            .unwrap_or((0.0, 1.0));
+        let position = (start + end) / 2.0;
+        let color = self.sample(position);
+        self.stops.push(GradientStop { position, color });
+        self.stops.len() - 1
+    }
+
+    /// Removes a stop unless only two are left
+    pub fn remove_stop(&mut self, index: usize) -> bool {
+        if self.stops.len() <= 2 || index >= self.stops.len() {
+            return false;
+        }
+        self.stops.remove(index);
+        true
+    }
+
+    /// A CSS `linear-gradient()` running left to right
+    pub fn to_css(&self) -> String {
+        let mut css = String::from("linear-gradient(to right");
+        // sRGB is the CSS default and needs no `in` clause
+        if self.interpolation != Interpolation::Srgb {
+            write!(css, " in {}", self.interpolation.css_name()).unwrap();
+        }
+        for stop in self.sorted_stops() {
+            write!(css, ", {} {}%", to_hex(stop.color), percent(stop.position)).unwrap();
+        }
+        css.push(')');
+        css
+    }
+
+    pub fn to_json(&self) -> String {
+        #[derive(Serialize)]
+        struct JsonGradient {
+            interpolation: &'static str,
+            stops: Vec<JsonStop>,
+        }
+
+        #[derive(Serialize)]
+        struct JsonStop {
+            position: f64,
+            color: String,
+        }
+
+        let json = JsonGradient {
+            interpolation: self.interpolation.css_name(),
+            stops: self
+                .sorted_stops()
+                .into_iter()
+                .map(|stop| JsonStop {
+                    position: (stop.position as f64 * 1000.0).round() / 1000.0,
+                    color: to_hex(stop.color),
+                })
+                .collect(),
+        };
+        let mut text =
+            serde_json::to_string_pretty(&json).expect("gradient is always serializable");
+        text.push('\n');
+        text
+    }
+
+    /// Samples `count` evenly spaced colors into a palette
+    pub fn to_palette(&self, name: impl Into<String>, count: usize) -> Palette {
+        let mut palette = Palette::new(name);
+        for color in self.steps(count) {
+            palette.add(color);
+        }
+        palette
+    }
+}
+
+// At most one decimal, without a trailing ".0"
+fn percent(position: f32) -> String {
+    let value = (position * 1000.0).round() / 10.0;
+    if value.fract() == 0.0 {
+        format!("{value:.0}")
+    } else {
+        format!("{value:.1}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::{rgb, rgba};
+
+    use super::*;
+
+    fn black_to_white(interpolation: Interpolation) -> Gradient {
+        Gradient {
+            interpolation,
+            ..Gradient::new(rgb(0x000000), rgb(0xffffff))
+        }
+    }
+
+    #[test]
+    fn midpoint_depends_on_interpolation_space() {
+        assert_eq!(
+            to_hex(black_to_white(Interpolation::Srgb).sample(0.5)),
+            "#808080"
+        );
+        assert_eq!(
+            to_hex(black_to_white(Interpolation::Linear).sample(0.5)),
+            "#bcbcbc"
+        );
+        assert_eq!(
+            to_hex(black_to_white(Interpolation::Oklab).sample(0.5)),
+            "#636363"
+        );
+    }
+
+    #[test]
+    fn samples_hold_outside_the_stops_and_respect_order() {
+        let mut gradient = Gradient::new(rgb(0xff0000), rgb(0x0000ff));
+        gradient.interpolation = Interpolation::Srgb;
+        gradient.stops[0].position = 0.25;
+        gradient.stops.push(GradientStop {
+            position: 0.5,
+            color: rgb(0x00ff00),
+        });
+        assert_eq!(to_hex(gradient.sample(0.0)), "#ff0000");
+        assert_eq!(to_hex(gradient.sample(0.5)), "#00ff00");
+        assert_eq!(to_hex(gradient.sample(0.75)), "#008080");
+        assert_eq!(to_hex(gradient.sample(1.0)), "#0000ff");
+    }
+
+    #[test]
+    fn transparent_stops_do_not_darken_the_blend() {
+        let gradient = Gradient {
+            interpolation: Interpolation::Srgb,
+            ..Gradient::new(rgba(0xff000000), rgb(0xff0000))
+        };
+        assert_eq!(to_hex(gradient.sample(0.5)), "#ff000080");
+    }
+
+    #[test]
+    fn adding_a_stop_keeps_the_gradient_unchanged() {
+        let mut gradient = black_to_white(Interpolation::Oklab);
+        let before = gradient.steps(9);
+        let index = gradient.add_stop();
+        assert_eq!(gradient.stops[index].position, 0.5);
+        let after = gradient.steps(9);
+        for (a, b) in before.iter().zip(&after) {
+            assert_eq!(to_hex(*a), to_hex(*b));
+        }
+
+        assert!(gradient.remove_stop(index));
+        assert!(!gradient.remove_stop(0));
+    }
+
+    #[test]
+    fn exports_css_json_and_palette() {
+        let mut gradient = Gradient::new(rgb(0x1976d2), rgb(0xffffff));
+        gradient.stops.push(GradientStop {
+            position: 0.333,
+            color: rgb(0xff8000),
+        });
+        assert_eq!(
+            gradient.to_css(),
+            "linear-gradient(to right in oklab, #1976d2 0%, #ff8000 33.3%, #ffffff 100%)"
+        );
+        gradient.interpolation = Interpolation::Srgb;
+        assert!(gradient.to_css().starts_with("linear-gradient(to right, "));
+
+        assert_eq!(
+            gradient.to_json(),
+            "{\n  \"interpolation\": \"srgb\",\n  \"stops\": [\n    {\n      \"position\": 0.0,\n      \"color\": \"#1976d2\"\n    },\n    {\n      \"position\": 0.333,\n      \"color\": \"#ff8000\"\n    },\n    {\n      \"position\": 1.0,\n      \"color\": \"#ffffff\"\n    }\n  ]\n}\n"
+        );
+
+        let palette = black_to_white(Interpolation::Srgb).to_palette("Ramp", 3);
+        assert_eq!(palette.name, "Ramp");
+        let hexes: Vec<_> = palette.swatches.iter().map(|s| to_hex(s.color)).collect();
+        assert_eq!(hexes, ["#000000", "#808080", "#ffffff"]);
+    }
+}
@@ -0,0 +1,164 @@
+use gpui::{Hsla, Rgba};
+
+use crate::color::{Oklch, clamp_rgb};
+
+/// Classic color-wheel relationships, expressed as hue rotations of a base color
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Harmony {
+    #[default]
+    Complementary,
+    Analogous,
+    Triadic,
+    SplitComplementary,
+    Tetradic,
+}
+
+impl Harmony {
+    pub const ALL: [Harmony; 5] = [
+        Harmony::Complementary,
+        Harmony::Analogous,
+        Harmony::Triadic,
+        Harmony::SplitComplementary,
+        Harmony::Tetradic,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Harmony::Complementary => "Complementary",
+            Harmony::Analogous => "Analogous",
+            Harmony::Triadic => "Triadic",
+            Harmony::SplitComplementary => "Split",
+            Harmony::Tetradic => "Tetradic",
+        }
+    }
+
+    /// Hue offsets in degrees, in order around the wheel. Every set includes
+    /// the base color itself at 0, which comes first except in `Analogous`,
+    /// where it sits between its neighbors.
+    pub fn offsets(self) -> &'static [f32] {
+        match self {
+            Harmony::Complementary => &[0.0, 180.0],
+            Harmony::Analogous => &[-30.0, 0.0, 30.0],
+            Harmony::Triadic => &[0.0, 120.0, 240.0],
+            Harmony::SplitComplementary => &[0.0, 150.0, 210.0],
+            Harmony::Tetradic => &[0.0, 90.0, 180.0, 270.0],
+        }
+    }
+}
+
+/// Space in which hues are rotated. OKLCH keeps perceived lightness constant
+/// across the set, HSL matches what most other tools produce.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HarmonySpace {
+    Hsl,
+    #[default]
+    Oklch,
+}
+
+impl HarmonySpace {
+    pub const ALL: [HarmonySpace; 2] = [HarmonySpace::Hsl, HarmonySpace::Oklch];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            HarmonySpace::Hsl => "HSL",
+            HarmonySpace::Oklch => "OKLCH",
+        }
+    }
+
+    /// Rotates the hue of `color` by `degrees`, keeping the other channels and alpha
+    pub fn rotate_hue(self, color: Rgba, degrees: f32) -> Rgba {
+        match self {
+            HarmonySpace::Hsl => {
+                let hsla = Hsla::from(color);
+                clamp_rgb(Rgba::from(Hsla {
+                    h: (hsla.h + degrees / 360.0).rem_euclid(1.0),
+                    ..hsla
+                }))
+            }
+            HarmonySpace::Oklch => {
+                let lch = Oklch::from(color);
+                Oklch {
+                    h: (lch.h + degrees).rem_euclid(360.0),
+                    ..lch
+                }
+                .to_rgb_clamped(color.a)
+            }
+        }
+    }
+}
+
+/// The colors of `harmony` built around `base`, in the order of `Harmony::offsets`
+pub fn generate(base: Rgba, harmony: Harmony, space: HarmonySpace) -> Vec<Rgba> {
+    harmony
+        .offsets()
+        .iter()
+        .map(|&offset| {
+            if offset == 0.0 {
+                base
+            } else {
+                space.rotate_hue(base, offset)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::rgb;
+
+    use super::*;
+    use crate::color::to_hex;
+
+    fn hexes(base: u32, harmony: Harmony, space: HarmonySpace) -> Vec<String> {
+        generate(rgb(base), harmony, space)
+            .into_iter()
+            .map(to_hex)
+            .collect()
+    }
+
+    #[test]
+    fn hsl_harmonies_rotate_around_the_wheel() {
+        assert_eq!(
+            hexes(0xff0000, Harmony::Complementary, HarmonySpace::Hsl),
+            ["#ff0000", "#00ffff"]
+        );
+        assert_eq!(
+            hexes(0xff0000, Harmony::Triadic, HarmonySpace::Hsl),
+            ["#ff0000", "#00ff00", "#0000ff"]
+        );
+        assert_eq!(
+            hexes(0xff0000, Harmony::Analogous, HarmonySpace::Hsl),
+            ["#ff0080", "#ff0000", "#ff8000"]
+        );
+        assert_eq!(
+            hexes(0xff0000, Harmony::Tetradic, HarmonySpace::Hsl),
+            ["#ff0000", "#80ff00", "#00ffff", "#8000ff"]
+        );
+    }
+
+    #[test]
+    fn oklch_harmonies_keep_lightness_and_hue_spacing() {
+        let base = rgb(0x3a7bd5);
+        let colors = generate(base, Harmony::SplitComplementary, HarmonySpace::Oklch);
+        assert_eq!(colors.len(), 3);
+        assert_eq!(colors[0], base);
+
+        let base_lch = Oklch::from(base);
+        for (color, offset) in colors.iter().zip(Harmony::SplitComplementary.offsets()) {
+            let lch = Oklch::from(*color);
+            assert!((lch.l - base_lch.l).abs() < 0.01, "{lch:?}");
+            let hue_delta = (lch.h - base_lch.h - offset).rem_euclid(360.0);
+            assert!(hue_delta.min(360.0 - hue_delta) < 2.0, "{lch:?}");
+        }
+    }
+
+    #[test]
+    fn gray_has_no_hue_to_rotate() {
+        for space in HarmonySpace::ALL {
+            assert_eq!(
+                hexes(0x808080, Harmony::Triadic, space),
+                ["#808080", "#808080", "#808080"]
+            );
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use gpui::Rgba;
+
+// Continuous edits closer together than this share one undo step
+const COALESCE_WINDOW: Duration = Duration::from_millis(500);
+
+/// Bounded undo/redo stacks of colors
+pub struct History {
+    // Oldest first
+    past: VecDeque<Rgba>,
+    future: Vec<Rgba>,
+    capacity: usize,
+    // Time of the last continuous edit, while a run of them is in progress
+    last_continuous: Option<Instant>,
+}
+
+impl History {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            past: VecDeque::with_capacity(capacity),
+            future: Vec::new(),
+            capacity,
+            last_continuous: None,
+        }
+    }
+
+    /// Restores saved undo steps, oldest first, keeping the newest `capacity`
+    pub fn with_past(capacity: usize, past: impl IntoIterator<Item = Rgba>) -> Self {
+        let mut history = Self::new(capacity);
+        for color in past {
+            if history.past.len() == capacity {
+                history.past.pop_front();
+            }
+            history.past.push_back(color);
+        }
+        history
+    }
+
+    /// Records `previous` as an undo step before the color changes. Continuous
+    /// edits such as slider drags are merged into the step that started them.
+    pub fn record(&mut self, previous: Rgba, continuous: bool, now: Instant) {
+        let coalesce = continuous
+            && self
+                .last_continuous
+                .is_some_and(|last| now.duration_since(last) < COALESCE_WINDOW);
+        self.last_continuous = continuous.then_some(now);
+        self.future.clear();
+        if coalesce {
+            return;
+        }
+
+        if self.past.len() == self.capacity {
+            self.past.pop_front();
+        }
+        self.past.push_back(previous);
+    }
+
+    /// Returns the color to restore, remembering `current` for redo
+    pub fn undo(&mut self, current: Rgba) -> Option<Rgba> {
+        let color = self.past.pop_back()?;
+        self.future.push(current);
+        self.last_continuous = None;
+        Some(color)
+    }
+
+    /// Returns the color to restore, remembering `current` for undo
+    pub fn redo(&mut self, current: Rgba) -> Option<Rgba> {
+        let color = self.future.pop()?;
+        self.past.push_back(current);
+        self.last_continuous = None;
+        Some(color)
+    }
+
+    /// Undoes `steps` times in one go, so everything skipped stays redoable
+    pub fn undo_many(&mut self, steps: usize, current: Rgba) -> Option<Rgba> {
+        if steps == 0 || steps > self.past.len() {
+            return None;
+        }
+        (0..steps).try_fold(current, |color, _| self.undo(color))
+    }
+
+    /// Previous colors, most recent first
+    pub fn recent(&self) -> impl Iterator<Item = Rgba> + '_ {
+        self.past.iter().rev().copied()
+    }
+
+    /// Previous colors, oldest first, as `with_past` takes them
+    pub fn past(&self) -> impl Iterator<Item = Rgba> + '_ {
+        self.past.iter().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::rgb;
+
+    use super::*;
+
+    #[test]
+    fn undo_and_redo_walk_the_stacks() {
+        let now = Instant::now();
+        let mut history = History::new(10);
+        history.record(rgb(0x000000), false, now);
+        history.record(rgb(0x111111), false, now);
+
+        assert_eq!(history.undo(rgb(0x222222)), Some(rgb(0x111111)));
+        assert_eq!(history.undo(rgb(0x111111)), Some(rgb(0x000000)));
+        assert_eq!(history.undo(rgb(0x000000)), None);
+        assert_eq!(history.redo(rgb(0x000000)), Some(rgb(0x111111)));
+        assert_eq!(history.redo(rgb(0x111111)), Some(rgb(0x222222)));
+        assert_eq!(history.redo(rgb(0x222222)), None);
+    }
+
+    #[test]
+    fn new_edits_clear_redo() {
+        let now = Instant::now();
+        let mut history = History::new(10);
+        history.record(rgb(0x000000), false, now);
+        history.undo(rgb(0x111111));
+        history.record(rgb(0x000000), false, now);
+        assert_eq!(history.redo(rgb(0x333333)), None);
+    }
+
+    #[test]
+    fn drags_coalesce_until_they_pause() {
+        let start = Instant::now();
+        let mut history = History::new(10);
+        for step in 0..20u64 {
+            history.record(
+                rgb(step as u32),
+                true,
+                start + Duration::from_millis(step * 16),
+            );
+        }
+        assert_eq!(history.recent().collect::<Vec<_>>(), [rgb(0)]);
+
+        // A pause starts a new step, and so does a discrete edit in between
+        history.record(rgb(0x100), true, start + Duration::from_secs(5));
+        history.record(rgb(0x200), false, start + Duration::from_secs(5));
+        history.record(rgb(0x300), true, start + Duration::from_secs(5));
+        assert_eq!(history.recent().count(), 4);
+    }
+
+    #[test]
+    fn keyboard_steps_are_separate_even_when_quick() {
+        let start = Instant::now();
+        let mut history = History::new(10);
+        history.record(rgb(0x000000), false, start);
+        history.record(rgb(0x010000), false, start + Duration::from_millis(30));
+        assert_eq!(
+            history.recent().collect::<Vec<_>>(),
+            [rgb(0x010000), rgb(0x000000)]
+        );
+        assert_eq!(history.undo(rgb(0x020000)), Some(rgb(0x010000)));
+        assert_eq!(history.undo(rgb(0x010000)), Some(rgb(0x000000)));
+    }
+
+    #[test]
+    fn capacity_drops_the_oldest_step() {
+        let now = Instant::now();
+        let mut history = History::new(3);
+        for value in 0..5 {
+            history.record(rgb(value), false, now);
+        }
+        assert_eq!(
+            history.recent().collect::<Vec<_>>(),
+            [rgb(4), rgb(3), rgb(2)]
+        );
+    }
+
+    #[test]
+    fn restoring_keeps_the_newest_steps() {
+        let history = History::with_past(2, [rgb(1), rgb(2), rgb(3)]);
+        assert_eq!(history.past().collect::<Vec<_>>(), [rgb(2), rgb(3)]);
+    }
+
+    #[test]
+    fn undo_many_keeps_skipped_steps_redoable() {
+        let now = Instant::now();
+        let mut history = History::new(10);
+        for value in 0..3 {
+            history.record(rgb(value), false, now);
+        }
+        assert_eq!(history.undo_many(2, rgb(3)), Some(rgb(1)));
+        assert_eq!(history.redo(rgb(1)), Some(rgb(2)));
+        assert_eq!(history.redo(rgb(2)), Some(rgb(3)));
+    }
+}
@@ -0,0 +1,22 @@
+//! Color math, palette formats and the command-line interface behind the
+//! color mixer, usable without opening a window
+
+pub mod blend;
+pub mod cli;
+pub mod color;
+pub mod contrast;
+pub mod distance;
+pub mod format;
+pub mod gradient;
+pub mod harmony;
+pub mod history;
+pub mod named_colors;
+pub mod palette;
+pub mod parse;
+#[cfg(feature = "image")]
+pub mod quantize;
+pub mod scale;
+#[cfg(feature = "state")]
+pub mod state;
+pub mod theme_colors;
+pub mod vision;
@@ -1,73 +1,1783 @@
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
 use gpui::{
-    App, Application, Bounds, Context, Window, WindowBounds, WindowOptions, div, prelude::*, px,
-    rgb, size,
+    Action, App, Application, Bounds, ClickEvent, ClipboardItem, Context, Div, ElementId, Entity,
+    FocusHandle, Focusable, Hsla, KeyBinding, MouseButton, MouseDownEvent, PathPromptOptions,
+    Pixels, Point, Rgba, SharedString, Stateful, Task, Window, WindowBounds, WindowOptions,
+    actions, anchored, deferred, div, img, linear_color_stop, linear_gradient, prelude::*, px,
+    relative, rgb, size,
 };
 
+mod checkerboard;
+mod color_input;
+mod picker;
+mod slider;
+
+use checkerboard::checkerboard;
+use color_input::{ColorCommitted, ColorInput};
+use gpui_color_mixer::blend::{self, BlendMode};
+use gpui_color_mixer::color::{self, ColorMode, Hsv};
+use gpui_color_mixer::contrast::{self, ContrastRole, ContrastTarget};
+use gpui_color_mixer::format::ColorFormat;
+use gpui_color_mixer::gradient::{Gradient, Interpolation};
+use gpui_color_mixer::harmony::{self, Harmony, HarmonySpace};
+use gpui_color_mixer::history::History;
+use gpui_color_mixer::palette::{Palette, export::ExportFormat, import};
+use gpui_color_mixer::quantize::SampledImage;
+use gpui_color_mixer::scale::Scale;
+use gpui_color_mixer::state::{self, MixerState, StateError};
+use gpui_color_mixer::theme_colors::THEME_COLORS;
+use gpui_color_mixer::vision::{self, Deficiency};
+use gpui_color_mixer::{cli, distance};
+use picker::{HueStrip, SaturationValueSquare};
+use slider::Slider;
+
+actions!(
+    color_mixer,
+    [
+        Undo,
+        Redo,
+        FocusNext,
+        FocusPrevious,
+        Increment,
+        Decrement,
+        IncrementCoarse,
+        DecrementCoarse,
+        SetMinimum,
+        SetMaximum,
+        CopyColor,
+    ]
+);
+
+// Undo steps kept, and how many of them the history strip shows
+const HISTORY_CAPACITY: usize = 100;
+const HISTORY_STRIP_LEN: usize = 12;
+
+// Channel rows are indexed 0..3 for the current mode, then alpha
+const ALPHA_CHANNEL: usize = 3;
+const ALPHA_STEP: f32 = 0.01;
+// Multiplier applied to the fine step by page-up/page-down
+const COARSE_STEPS: f32 = 10.0;
+
+// Segments used to draw the gradient preview, and the range of sampled steps
+const GRADIENT_PREVIEW_SEGMENTS: usize = 48;
+const GRADIENT_STEPS: std::ops::RangeInclusive<usize> = 2..=32;
+
+// Palette sizes offered for image extraction, and the box an opened image is fit into
+const IMAGE_PALETTE_SIZES: std::ops::RangeInclusive<usize> = 5..=16;
+const IMAGE_MAX_WIDTH: f32 = 528.0;
+const IMAGE_MAX_HEIGHT: f32 = 200.0;
+
+// Intermediate results shown between blend colors A and B
+const BLEND_STEPS: usize = 9;
+
+// Range of the tint/shade scale's curve exponent; the slider is logarithmic
+// so 1.0, even spacing, sits in the middle
+const SCALE_CURVE_MIN: f32 = 0.5;
+const SCALE_CURVE_MAX: f32 = 2.0;
+
+// Quiet period after the last change before state is written to disk
+const SAVE_DELAY: Duration = Duration::from_millis(1000);
+
+// How long the "Copied" toast stays up
+const TOAST_DURATION: Duration = Duration::from_millis(1500);
+
+// Format menu opened by right-clicking the preview or a swatch
+struct CopyMenu {
+    color: Rgba,
+    position: Point<Pixels>,
+    // Palette swatch the menu was opened on, which can also be removed from it
+    swatch: Option<usize>,
+}
+
 struct ColorMixer {
+    focus_handle: FocusHandle,
+    channel_focus: [FocusHandle; 4],
     red: f32,
     green: f32,
     blue: f32,
+    alpha: f32,
+    mode: ColorMode,
+    // Position of the picker, which also remembers hue and saturation for grays
+    picker: Hsv,
+    harmony: Harmony,
+    harmony_space: HarmonySpace,
+    // The other half of the text/background pair checked for contrast
+    contrast_partner: Rgba,
+    contrast_role: ContrastRole,
+    contrast_target: ContrastTarget,
+    // Applied to the preview and swatches only; stored colors are unaffected
+    simulation: Option<Deficiency>,
+    gradient: Gradient,
+    // While a stop is selected, color edits apply to it
+    selected_stop: Option<usize>,
+    gradient_steps: usize,
+    image: Option<(PathBuf, SampledImage)>,
+    image_palette_size: usize,
+    scale_curve: f32,
+    blend_colors: [Rgba; 2],
+    blend_mode: BlendMode,
+    // Only used by `BlendMode::Normal`
+    blend_space: Interpolation,
+    blend_ratio: f32,
+    input: Entity<ColorInput>,
+    history: History,
+    palette: Palette,
+    status: Option<SharedString>,
+    copy_menu: Option<CopyMenu>,
+    // Last format picked from the menu, reused by the copy key binding
+    copy_format: ColorFormat,
+    // Swatch under the pointer, which the copy key binding copies instead of
+    // the current color
+    hovered_swatch: Option<Rgba>,
+    toast: Option<SharedString>,
+    // Hides the toast; replacing it cancels the previous timer
+    toast_timer: Option<Task<()>>,
+    // Where state persists between launches; `None` if there's no data directory
+    state_path: Option<PathBuf>,
+    // What is on disk, to skip saves that would change nothing
+    saved_state: Option<MixerState>,
+    save_task: Option<Task<()>>,
 }
 
 impl ColorMixer {
-    fn new(_cx: &mut Context<Self>) -> Self {
-        Self {
+    fn new(cx: &mut Context<Self>) -> Self {
+        let input = cx.new(|cx| ColorInput::new("#808080".into(), cx));
+        cx.subscribe(&input, |this, _, event: &ColorCommitted, cx| {
+            this.set_color(event.0, cx);
+        })
+        .detach();
+        cx.on_app_quit(|this, _| {
+            this.save_now();
+            async {}
+        })
+        .detach();
+
+        let mut mixer = Self {
+            focus_handle: cx.focus_handle(),
+            channel_focus: [1, 2, 3, 4]
+                .map(|index| cx.focus_handle().tab_index(index).tab_stop(true)),
             red: 0.5,
             green: 0.5,
             blue: 0.5,
+            alpha: 1.0,
+            mode: ColorMode::default(),
+            picker: Hsv {
+                h: 0.0,
+                s: 0.0,
+                v: 0.5,
+            },
+            harmony: Harmony::default(),
+            harmony_space: HarmonySpace::default(),
+            contrast_partner: rgb(0xffffff),
+            contrast_role: ContrastRole::default(),
+            contrast_target: ContrastTarget::default(),
+            simulation: None,
+            gradient: Gradient::new(rgb(0x1976d2), rgb(0xffc107)),
+            selected_stop: None,
+            gradient_steps: 5,
+            image: None,
+            image_palette_size: 8,
+            scale_curve: 1.0,
+            blend_colors: [rgb(0x1976d2), rgb(0xffc107)],
+            blend_mode: BlendMode::default(),
+            blend_space: Interpolation::default(),
+            blend_ratio: 0.5,
+            input,
+            history: History::new(HISTORY_CAPACITY),
+            palette: Palette::new("Palette"),
+            status: None,
+            copy_menu: None,
+            copy_format: ColorFormat::default(),
+            hovered_swatch: None,
+            toast: None,
+            toast_timer: None,
+            state_path: state::default_path(),
+            saved_state: None,
+            save_task: None,
+        };
+        mixer.restore_state(cx);
+        mixer
+    }
+
+    fn mixer_state(&self) -> MixerState {
+        MixerState {
+            color: self.color(),
+            mode: self.mode,
+            palette: self.palette.clone(),
+            history: self.history.past().collect(),
+        }
+    }
+
+    fn restore_state(&mut self, cx: &mut Context<Self>) {
+        let Some(path) = self.state_path.clone() else {
+            return;
+        };
+        match state::load(&path) {
+            Ok(Some(saved)) => {
+                self.mode = saved.mode;
+                self.palette = saved.palette.clone();
+                self.history = History::with_past(HISTORY_CAPACITY, saved.history.iter().copied());
+                let color = saved.color;
+                self.saved_state = Some(saved);
+                self.show_color(color, cx);
+            }
+            Ok(None) => {}
+            Err(error @ StateError::Corrupt(_)) => {
+                // Start fresh, keeping the unreadable file for inspection
+                let message = match state::set_aside(&path) {
+                    Some(backup) => format!("{error}; moved to {}", backup.display()),
+                    None => error.to_string(),
+                };
+                self.status = Some(message.into());
+            }
+            Err(error) => {
+                // A newer build's file, or one we can't read, is left alone and
+                // nothing is saved over it this session
+                self.state_path = None;
+                self.status = Some(format!("{error}; changes won't be saved").into());
+            }
+        }
+    }
+
+    // Called whenever a persisted field changes (color, mode, palette or
+    // history); writes once changes have settled for `SAVE_DELAY`
+    fn schedule_save(&mut self, cx: &mut Context<Self>) {
+        let Some(path) = self.state_path.clone() else {
+            return;
+        };
+        let current = self.mixer_state();
+        if self.saved_state.as_ref() == Some(&current) {
+            self.save_task = None;
+            return;
         }
+
+        self.save_task = Some(cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(SAVE_DELAY).await;
+            let result = cx
+                .background_executor()
+                .spawn({
+                    let current = current.clone();
+                    async move { state::save(&path, &current) }
+                })
+                .await;
+            this.update(cx, |this, cx| match result {
+                Ok(()) => this.saved_state = Some(current),
+                Err(error) => {
+                    this.status = Some(format!("Can't save state: {error}").into());
+                    cx.notify();
+                }
+            })
+            .ok();
+        }));
+    }
+
+    // Flushes a pending save when the app quits
+    fn save_now(&mut self) {
+        let Some(path) = &self.state_path else {
+            return;
+        };
+        let current = self.mixer_state();
+        if self.saved_state.as_ref() != Some(&current) && state::save(path, &current).is_ok() {
+            self.saved_state = Some(current);
+            self.save_task = None;
+        }
+    }
+
+    fn color(&self) -> Rgba {
+        Rgba {
+            r: self.red,
+            g: self.green,
+            b: self.blue,
+            a: self.alpha,
+        }
+    }
+
+    fn channel(&self, index: usize) -> f32 {
+        if index == ALPHA_CHANNEL {
+            self.alpha
+        } else {
+            self.mode.decompose(self.color())[index]
+        }
+    }
+
+    // Edits one channel of the current mode and converts back to RGB;
+    // `continuous` is only set by slider drags
+    fn set_channel(&mut self, index: usize, value: f32, continuous: bool, cx: &mut Context<Self>) {
+        if index == ALPHA_CHANNEL {
+            return self.set_alpha(value, continuous, cx);
+        }
+        let mut channels = self.mode.decompose(self.color());
+        channels[index] = value;
+        self.edit_color(self.mode.compose(channels, self.alpha), continuous, cx);
+    }
+
+    fn set_color(&mut self, color: Rgba, cx: &mut Context<Self>) {
+        self.edit_color(color, false, cx);
+    }
+
+    // Records an undo step for `color` unless it changes nothing; `continuous`
+    // marks slider drags so they collapse into a single step. The selected
+    // gradient stop follows edits, but not undo and redo.
+    fn edit_color(&mut self, color: Rgba, continuous: bool, cx: &mut Context<Self>) {
+        if color != self.color() {
+            self.history
+                .record(self.color(), continuous, Instant::now());
+        }
+        if let Some(stop) = self
+            .selected_stop
+            .and_then(|index| self.gradient.stops.get_mut(index))
+        {
+            stop.color = color;
+        }
+        self.show_color(color, cx);
     }
 
-    fn set_red(&mut self, value: f32, cx: &mut Context<Self>) {
-        self.red = value;
+    // Replaces the color without touching the history
+    fn show_color(&mut self, color: Rgba, cx: &mut Context<Self>) {
+        self.red = color.r;
+        self.green = color.g;
+        self.blue = color.b;
+        self.alpha = color.a;
+        self.picker = Hsv::from(color).keep_undefined_from(self.picker);
+        let hex = color::to_hex(self.color());
+        self.input.update(cx, |input, cx| input.set_text(hex, cx));
+        self.schedule_save(cx);
         cx.notify();
     }
 
-    fn set_green(&mut self, value: f32, cx: &mut Context<Self>) {
-        self.green = value;
+    fn set_picker(&mut self, hsv: Hsv, cx: &mut Context<Self>) {
+        self.picker = hsv;
+        self.edit_color(hsv.to_rgb(self.alpha), true, cx);
+    }
+
+    fn set_alpha(&mut self, alpha: f32, continuous: bool, cx: &mut Context<Self>) {
+        self.edit_color(
+            Rgba {
+                a: alpha,
+                ..self.color()
+            },
+            continuous,
+            cx,
+        );
+    }
+
+    fn undo(&mut self, _: &Undo, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(color) = self.history.undo(self.color()) {
+            self.show_color(color, cx);
+        }
+    }
+
+    fn redo(&mut self, _: &Redo, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(color) = self.history.redo(self.color()) {
+            self.show_color(color, cx);
+        }
+    }
+
+    // Moves a channel by `steps` of its fine keyboard step, each press its
+    // own undo step
+    fn step_channel(&mut self, index: usize, steps: f32, cx: &mut Context<Self>) {
+        let step = if index == ALPHA_CHANNEL {
+            ALPHA_STEP
+        } else {
+            self.mode.step(index)
+        };
+        let value = (self.channel(index) + steps * step).clamp(0.0, 1.0);
+        self.set_channel(index, value, false, cx);
+    }
+
+    // `index` counts back from the most recent entry in the history strip
+    fn revert_to(&mut self, index: usize, cx: &mut Context<Self>) {
+        if let Some(color) = self.history.undo_many(index + 1, self.color()) {
+            self.show_color(color, cx);
+        }
+    }
+
+    // Returns (text, background)
+    fn contrast_pair(&self) -> (Rgba, Rgba) {
+        match self.contrast_role {
+            ContrastRole::Text => (self.color(), self.contrast_partner),
+            ContrastRole::Background => (self.contrast_partner, self.color()),
+        }
+    }
+
+    fn swap_contrast_pair(&mut self, cx: &mut Context<Self>) {
+        let current = self.color();
+        let partner = std::mem::replace(&mut self.contrast_partner, current);
+        self.set_color(partner, cx);
+    }
+
+    // Moves the current color's lightness just far enough to meet the target
+    fn nudge_contrast(&mut self, cx: &mut Context<Self>) {
+        let (partner, role, target) = (
+            self.contrast_partner,
+            self.contrast_role,
+            self.contrast_target,
+        );
+        let passes = |color| match role {
+            ContrastRole::Text => target.is_met(color, partner),
+            ContrastRole::Background => target.is_met(partner, color),
+        };
+        match contrast::nudge_lightness(self.color(), passes) {
+            Some(color) => self.set_color(color, cx),
+            None => {
+                self.status = Some(format!("No lightness reaches {}", target.label()).into());
+                cx.notify();
+            }
+        }
+    }
+
+    fn set_mode(&mut self, mode: ColorMode, cx: &mut Context<Self>) {
+        self.mode = mode;
+        self.schedule_save(cx);
         cx.notify();
     }
 
-    fn set_blue(&mut self, value: f32, cx: &mut Context<Self>) {
-        self.blue = value;
+    fn palette_changed(&mut self, cx: &mut Context<Self>) {
+        self.schedule_save(cx);
         cx.notify();
     }
 
-    fn render_channel_control(
-        &self,
-        label: &'static str,
-        value: f32,
-        setter: impl Fn(&mut Self, f32, &mut Context<Self>) + 'static + Copy,
+    fn add_to_palette(&mut self, cx: &mut Context<Self>) {
+        self.palette.add(self.color());
+        self.palette_changed(cx);
+    }
+
+    fn add_harmony_to_palette(&mut self, cx: &mut Context<Self>) {
+        for color in harmony::generate(self.color(), self.harmony, self.harmony_space) {
+            self.palette.add(color);
+        }
+        self.palette_changed(cx);
+    }
+
+    fn remove_from_palette(&mut self, index: usize, cx: &mut Context<Self>) {
+        self.palette.remove(index);
+        self.palette_changed(cx);
+    }
+
+    fn export_palette(&mut self, format: ExportFormat, cx: &mut Context<Self>) {
+        let contents = format.export(&self.palette);
+        self.save_file(contents, format!("palette.{}", format.extension()), cx);
+    }
+
+    // Asks for a destination and writes `contents` there, reporting in the status line
+    fn save_file(&mut self, contents: Vec<u8>, suggested_name: String, cx: &mut Context<Self>) {
+        self.save_file_with(suggested_name, move |_| contents, cx);
+    }
+
+    // Like `save_file`, for contents that depend on the path the user picks
+    fn save_file_with(
+        &mut self,
+        suggested_name: String,
+        contents: impl FnOnce(&Path) -> Vec<u8> + 'static,
+        cx: &mut Context<Self>,
+    ) {
+        let directory = std::env::current_dir().unwrap_or_default();
+        let path = cx.prompt_for_new_path(&directory, Some(&suggested_name));
+
+        cx.spawn(async move |this, cx| {
+            let status = match path.await {
+                Ok(Ok(Some(path))) => match std::fs::write(&path, contents(&path)) {
+                    Ok(()) => format!("Exported {}", path.display()),
+                    Err(error) => format!("Export failed: {error}"),
+                },
+                Ok(Err(error)) => format!("Export failed: {error}"),
+                // The user cancelled the dialog
+                Ok(Ok(None)) | Err(_) => return,
+            };
+            this.update(cx, |this, cx| {
+                this.status = Some(status.into());
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn scale(&self) -> Scale {
+        Scale::new(self.color(), self.scale_curve)
+    }
+
+    // The token group takes its name from the file, e.g. `brand.json` -> "brand"
+    fn export_scale(&mut self, tokens: bool, cx: &mut Context<Self>) {
+        let scale = self.scale();
+        let (name, _) = distance::nearest_named(self.color());
+        let extension = if tokens { "json" } else { "css" };
+        self.save_file_with(
+            format!("{name}.{extension}"),
+            move |path| {
+                let name = path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                if tokens {
+                    scale.to_tokens(&name).into_bytes()
+                } else {
+                    scale.to_css(&name).into_bytes()
+                }
+            },
+            cx,
+        );
+    }
+
+    fn add_scale_to_palette(&mut self, cx: &mut Context<Self>) {
+        let (name, _) = distance::nearest_named(self.color());
+        self.palette
+            .swatches
+            .extend(self.scale().to_palette(name).swatches);
+        self.palette_changed(cx);
+    }
+
+    fn blend_result(&self) -> Rgba {
+        let [a, b] = self.blend_colors;
+        blend::blend(a, b, self.blend_mode, self.blend_space, self.blend_ratio)
+    }
+
+    fn add_blend_steps_to_palette(&mut self, cx: &mut Context<Self>) {
+        let [a, b] = self.blend_colors;
+        for color in blend::steps(a, b, self.blend_mode, self.blend_space, BLEND_STEPS) {
+            self.palette.add(color);
+        }
+        self.palette_changed(cx);
+    }
+
+    fn open_copy_menu(
+        &mut self,
+        color: Rgba,
+        position: Point<Pixels>,
+        swatch: Option<usize>,
         cx: &mut Context<Self>,
-    ) -> impl IntoElement {
+    ) {
+        self.copy_menu = Some(CopyMenu {
+            color,
+            position,
+            swatch,
+        });
+        cx.notify();
+    }
+
+    fn close_copy_menu(&mut self, cx: &mut Context<Self>) {
+        self.copy_menu = None;
+        cx.notify();
+    }
+
+    fn copy(&mut self, color: Rgba, format: ColorFormat, cx: &mut Context<Self>) {
+        let text = format.format(color);
+        cx.write_to_clipboard(ClipboardItem::new_string(text.clone()));
+        self.copy_format = format;
+        self.copy_menu = None;
+        self.show_toast(format!("Copied {text}").into(), cx);
+    }
+
+    // The swatch the copy menu is open on, else the hovered one, else the
+    // current color
+    fn copy_color(&mut self, _: &CopyColor, _: &mut Window, cx: &mut Context<Self>) {
+        let color = self
+            .copy_menu
+            .as_ref()
+            .map(|menu| menu.color)
+            .or(self.hovered_swatch)
+            .unwrap_or_else(|| self.color());
+        self.copy(color, self.copy_format, cx);
+    }
+
+    fn show_toast(&mut self, message: SharedString, cx: &mut Context<Self>) {
+        self.toast = Some(message);
+        self.toast_timer = Some(cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(TOAST_DURATION).await;
+            this.update(cx, |this, cx| {
+                this.toast = None;
+                cx.notify();
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn open_image(&mut self, cx: &mut Context<Self>) {
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: Some("Open Image".into()),
+        });
+
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(mut paths))) = paths.await else {
+                return;
+            };
+            if paths.is_empty() {
+                return;
+            }
+            let path = paths.remove(0);
+            let result = cx
+                .background_executor()
+                .spawn(async move { SampledImage::open(&path).map(|image| (path, image)) })
+                .await;
+            this.update(cx, |this, cx| {
+                match result {
+                    Ok(image) => this.image = Some(image),
+                    Err(error) => this.status = Some(format!("Can't open image: {error}").into()),
+                }
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    // Eyedropper; `x` and `y` are fractions of the displayed image. Each
+    // click is its own undo step.
+    fn sample_image(&mut self, x: f32, y: f32, cx: &mut Context<Self>) {
+        if let Some((_, image)) = &self.image {
+            let color = image.pixel_at(x, y);
+            self.edit_color(color, false, cx);
+        }
+    }
+
+    fn add_image_palette(&mut self, cx: &mut Context<Self>) {
+        if let Some((_, image)) = &self.image {
+            for color in image.palette(self.image_palette_size) {
+                self.palette.add(color);
+            }
+            self.palette_changed(cx);
+        }
+    }
+
+    fn select_stop(&mut self, index: Option<usize>, cx: &mut Context<Self>) {
+        self.selected_stop = index;
+        // Loading the stop into the editor isn't an edit, so it isn't undoable
+        match index.and_then(|index| self.gradient.stops.get(index)) {
+            Some(stop) => self.show_color(stop.color, cx),
+            None => cx.notify(),
+        }
+    }
+
+    fn add_gradient_stop(&mut self, cx: &mut Context<Self>) {
+        let index = self.gradient.add_stop();
+        self.select_stop(Some(index), cx);
+    }
+
+    fn remove_gradient_stop(&mut self, cx: &mut Context<Self>) {
+        if let Some(index) = self.selected_stop
+            && self.gradient.remove_stop(index)
+        {
+            self.selected_stop = None;
+            cx.notify();
+        }
+    }
+
+    fn set_stop_position(&mut self, position: f32, cx: &mut Context<Self>) {
+        if let Some(stop) = self
+            .selected_stop
+            .and_then(|index| self.gradient.stops.get_mut(index))
+        {
+            stop.position = position;
+            cx.notify();
+        }
+    }
+
+    fn export_gradient_css(&mut self, cx: &mut Context<Self>) {
+        let css = format!(":root {{\n  --gradient: {};\n}}\n", self.gradient.to_css());
+        self.save_file(css.into_bytes(), "gradient.css".into(), cx);
+    }
+
+    fn export_gradient_json(&mut self, cx: &mut Context<Self>) {
+        let json = self.gradient.to_json();
+        self.save_file(json.into_bytes(), "gradient.json".into(), cx);
+    }
+
+    // Saves the sampled steps in the palette JSON format, so they can be imported again
+    fn export_gradient_palette(&mut self, cx: &mut Context<Self>) {
+        let palette = self.gradient.to_palette("Gradient", self.gradient_steps);
+        let contents = ExportFormat::Json.export(&palette);
+        self.save_file(contents, "gradient-palette.json".into(), cx);
+    }
+
+    fn add_gradient_to_palette(&mut self, cx: &mut Context<Self>) {
+        for color in self.gradient.steps(self.gradient_steps) {
+            self.palette.add(color);
+        }
+        self.palette_changed(cx);
+    }
+
+    fn import_palette(&mut self, cx: &mut Context<Self>) {
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: None,
+        });
+
+        cx.spawn(async move |this, cx| {
+            let path = match paths.await {
+                Ok(Ok(Some(mut paths))) if !paths.is_empty() => paths.remove(0),
+                Ok(Err(error)) => {
+                    this.update(cx, |this, cx| {
+                        this.status = Some(format!("Import failed: {error}").into());
+                        cx.notify();
+                    })
+                    .ok();
+                    return;
+                }
+                // The user cancelled the dialog
+                _ => return,
+            };
+            let result = cx
+                .background_executor()
+                .spawn(async move { import::import(&path).map(|palette| (path, palette)) })
+                .await;
+            this.update(cx, |this, cx| {
+                let status = match result {
+                    // Appended, so nothing already in the palette is lost
+                    Ok((path, palette)) => {
+                        let count = palette.swatches.len();
+                        this.palette.swatches.extend(palette.swatches);
+                        this.schedule_save(cx);
+                        format!("Added {count} colors from {}", path.display())
+                    }
+                    Err(error) => format!("Import failed: {error}"),
+                };
+                this.status = Some(status.into());
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    // Swatches in a column beside the preview; clicking one makes it the current color
+    fn render_swatch_strip(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .id("swatch-strip")
+            .flex()
+            .flex_col()
+            .flex_none()
+            .gap_1()
+            .p_1()
+            .bg(rgb(0x303030))
+            .overflow_y_scroll()
+            .children(
+                self.palette
+                    .swatches
+                    .iter()
+                    .enumerate()
+                    .map(|(index, swatch)| {
+                        let color = swatch.color;
+                        div()
+                            .id(("swatch", index))
+                            .size_6()
+                            .rounded_sm()
+                            .border_1()
+                            .border_color(rgb(0x505050))
+                            .bg(vision::simulate(self.simulation, color))
+                            .cursor_pointer()
+                            .on_click(cx.listener(move |this, _, _, cx| this.set_color(color, cx)))
+                            .on_hover(Self::swatch_hover_listener(color, cx))
+                            // Right-click offers copying or removing the swatch
+                            .on_mouse_down(
+                                MouseButton::Right,
+                                cx.listener(move |this, event: &MouseDownEvent, _, cx| {
+                                    this.open_copy_menu(color, event.position, Some(index), cx)
+                                }),
+                            )
+                    }),
+            )
+    }
+
+    fn render_palette(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .child(
+                div()
+                    .flex()
+                    .justify_between()
+                    .items_center()
+                    .child(format!(
+                        "{} ({} colors)",
+                        self.palette.name,
+                        self.palette.swatches.len()
+                    ))
+                    .child(
+                        div()
+                            .flex()
+                            .gap_1()
+                            .child(
+                                button("import-palette", "Import").on_click(
+                                    cx.listener(|this, _, _, cx| this.import_palette(cx)),
+                                ),
+                            )
+                            .child(
+                                button("add-swatch", "+ Add").on_click(
+                                    cx.listener(|this, _, _, cx| this.add_to_palette(cx)),
+                                ),
+                            ),
+                    ),
+            )
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_1()
+                    .child("Export")
+                    .children(ExportFormat::ALL.into_iter().map(|format| {
+                        button(format.label(), format.label()).on_click(
+                            cx.listener(move |this, _, _, cx| this.export_palette(format, cx)),
+                        )
+                    })),
+            )
+            .children(
+                self.status
+                    .clone()
+                    .map(|status| div().text_sm().text_color(rgb(0xb0b0b0)).child(status)),
+            )
+    }
+
+    fn render_harmonies(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let colors = harmony::generate(self.color(), self.harmony, self.harmony_space);
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .child(
+                div()
+                    .flex()
+                    .justify_between()
+                    .items_center()
+                    .child("Harmony")
+                    .child(
+                        div()
+                            .flex()
+                            .gap_1()
+                            .children(HarmonySpace::ALL.into_iter().map(|space| {
+                                button(("harmony-space", space as usize), space.label())
+                                    .when(space == self.harmony_space, |this| {
+                                        this.bg(rgb(0x505050))
+                                    })
+                                    .on_click(cx.listener(move |this, _, _, cx| {
+                                        this.harmony_space = space;
+                                        cx.notify();
+                                    }))
+                            })),
+                    ),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_wrap()
+                    .gap_1()
+                    .children(Harmony::ALL.into_iter().map(|harmony| {
+                        button(harmony.label(), harmony.label())
+                            .when(harmony == self.harmony, |this| this.bg(rgb(0x505050)))
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.harmony = harmony;
+                                cx.notify();
+                            }))
+                    })),
+            )
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_1()
+                    .children(colors.into_iter().enumerate().map(|(index, color)| {
+                        div()
+                            .id(("harmony", index))
+                            .flex_1()
+                            .h_8()
+                            .rounded_sm()
+                            .border_1()
+                            .border_color(rgb(0x505050))
+                            .bg(vision::simulate(self.simulation, color))
+                            .cursor_pointer()
+                            .on_click(cx.listener(move |this, _, _, cx| this.set_color(color, cx)))
+                            .on_mouse_down(MouseButton::Right, Self::copy_menu_listener(color, cx))
+                            .on_hover(Self::swatch_hover_listener(color, cx))
+                    }))
+                    .child(
+                        button("add-harmony", "+ Add all").on_click(
+                            cx.listener(|this, _, _, cx| this.add_harmony_to_palette(cx)),
+                        ),
+                    ),
+            )
+    }
+
+    fn render_contrast(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let (text, background) = self.contrast_pair();
+        let ratio = contrast::contrast_ratio(text, background);
+        let lc = contrast::apca_contrast(text, background);
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .child(
+                div()
+                    .flex()
+                    .justify_between()
+                    .items_center()
+                    .child("Contrast")
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap_1()
+                            .child(div().text_sm().text_color(rgb(0xb0b0b0)).child("Editing"))
+                            .children(ContrastRole::ALL.into_iter().map(|role| {
+                                button(("contrast-role", role as usize), role.label())
+                                    .when(role == self.contrast_role, |this| this.bg(rgb(0x505050)))
+                                    .on_click(cx.listener(move |this, _, _, cx| {
+                                        this.contrast_role = role;
+                                        cx.notify();
+                                    }))
+                            }))
+                            .child(
+                                div()
+                                    .size_6()
+                                    .rounded_sm()
+                                    .border_1()
+                                    .border_color(rgb(0x505050))
+                                    .bg(self.contrast_partner),
+                            )
+                            .child(button("swap-contrast", "Swap").on_click(
+                                cx.listener(|this, _, _, cx| this.swap_contrast_pair(cx)),
+                            )),
+                    ),
+            )
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child(format!("{ratio:.2}:1"))
+                    .children(ContrastTarget::WCAG.into_iter().map(|target| {
+                        let passed = target.is_met(text, background);
+                        div()
+                            .px_2()
+                            .rounded_sm()
+                            .text_sm()
+                            .bg(if passed { rgb(0x2e7d32) } else { rgb(0xc62828) })
+                            .child(format!(
+                                "{} {}",
+                                target.label(),
+                                if passed { "✓" } else { "✗" }
+                            ))
+                    }))
+                    .child(
+                        div()
+                            .text_color(rgb(0xb0b0b0))
+                            .child(format!("APCA Lc {lc:.1}")),
+                    ),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_wrap()
+                    .items_center()
+                    .gap_1()
+                    .children(ContrastTarget::ALL.into_iter().map(|target| {
+                        button(("contrast-target", target as usize), target.label())
+                            .when(target == self.contrast_target, |this| {
+                                this.bg(rgb(0x505050))
+                            })
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.contrast_target = target;
+                                cx.notify();
+                            }))
+                    }))
+                    .child(
+                        button("nudge-contrast", "Nudge to pass")
+                            .on_click(cx.listener(|this, _, _, cx| this.nudge_contrast(cx))),
+                    ),
+            )
+    }
+
+    fn render_vision_switcher(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let options = [None].into_iter().chain(Deficiency::ALL.map(Some));
+
+        div()
+            .flex()
+            .items_center()
+            .gap_1()
+            .child(div().text_sm().text_color(rgb(0xb0b0b0)).child("Vision"))
+            .children(options.enumerate().map(|(index, simulation)| {
+                let label = simulation.map_or("Normal", Deficiency::label);
+                button(("vision", index), label)
+                    .when(simulation == self.simulation, |this| this.bg(rgb(0x505050)))
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        this.simulation = simulation;
+                        cx.notify();
+                    }))
+            }))
+    }
+
+    fn render_picker(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let picker = self.picker;
+
+        div()
+            .flex()
+            .flex_col()
+            .flex_none()
+            .w(px(200.0))
+            .gap_2()
+            .p_2()
+            .bg(rgb(0x303030))
+            .child(
+                SaturationValueSquare::new("sv-square", picker)
+                    .on_change(cx.listener(|this, hsv, _, cx| this.set_picker(*hsv, cx))),
+            )
+            .child(HueStrip::new("hue-strip", picker.h).on_change(cx.listener(
+                move |this, hue, _, cx| {
+                    this.set_picker(
+                        Hsv {
+                            h: *hue,
+                            ..this.picker
+                        },
+                        cx,
+                    )
+                },
+            )))
+    }
+
+    fn render_gradient(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let samples = self.gradient.steps(GRADIENT_PREVIEW_SEGMENTS + 1);
+        let selected = self
+            .selected_stop
+            .and_then(|index| self.gradient.stops.get(index));
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .child(
+                div()
+                    .flex()
+                    .justify_between()
+                    .items_center()
+                    .child("Gradient")
+                    .child(
+                        div()
+                            .flex()
+                            .gap_1()
+                            .children(Interpolation::ALL.into_iter().map(|interpolation| {
+                                button(
+                                    ("interpolation", interpolation as usize),
+                                    interpolation.label(),
+                                )
+                                .when(interpolation == self.gradient.interpolation, |this| {
+                                    this.bg(rgb(0x505050))
+                                })
+                                .on_click(cx.listener(
+                                    move |this, _, _, cx| {
+                                        this.gradient.interpolation = interpolation;
+                                        cx.notify();
+                                    },
+                                ))
+                            })),
+                    ),
+            )
+            // Piecewise two-stop gradients between closely spaced samples
+            .child(
+                div()
+                    .relative()
+                    .h_8()
+                    .rounded_md()
+                    .overflow_hidden()
+                    .child(checkerboard().absolute().size_full())
+                    .child(
+                        div()
+                            .absolute()
+                            .size_full()
+                            .flex()
+                            .children(samples.windows(2).map(|pair| {
+                                div().flex_1().h_full().bg(linear_gradient(
+                                    90.0,
+                                    linear_color_stop(
+                                        vision::simulate(self.simulation, pair[0]),
+                                        0.0,
+                                    ),
+                                    linear_color_stop(
+                                        vision::simulate(self.simulation, pair[1]),
+                                        1.0,
+                                    ),
+                                ))
+                            })),
+                    ),
+            )
+            // Stop handles; clicking one edits it with the channel controls
+            .child(div().relative().h_3().mx(px(6.0)).children(
+                self.gradient.stops.iter().enumerate().map(|(index, stop)| {
+                    let is_selected = self.selected_stop == Some(index);
+                    div()
+                        .id(("gradient-stop", index))
+                        .absolute()
+                        .top_0()
+                        .left(relative(stop.position))
+                        .ml(px(-6.0))
+                        .size_3()
+                        .rounded_sm()
+                        .border_2()
+                        .border_color(if is_selected {
+                            rgb(0xffffff)
+                        } else {
+                            rgb(0x505050)
+                        })
+                        .bg(vision::simulate(self.simulation, stop.color))
+                        .cursor_pointer()
+                        .on_click(cx.listener(move |this, _, _, cx| {
+                            let index = (!is_selected).then_some(index);
+                            this.select_stop(index, cx)
+                        }))
+                }),
+            ))
+            .children(selected.map(|stop| {
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child("Position")
+                    .child(div().flex_1().child(
+                        Slider::new("stop-position", stop.position).on_change(
+                            cx.listener(|this, value, _, cx| this.set_stop_position(*value, cx)),
+                        ),
+                    ))
+                    .child(
+                        div()
+                            .w_12()
+                            .text_color(rgb(0xb0b0b0))
+                            .child(format!("{:.0}%", stop.position * 100.0)),
+                    )
+                    .child(
+                        button("remove-stop", "Remove")
+                            .on_click(cx.listener(|this, _, _, cx| this.remove_gradient_stop(cx))),
+                    )
+                    .child(
+                        button("deselect-stop", "Done")
+                            .on_click(cx.listener(|this, _, _, cx| this.select_stop(None, cx))),
+                    )
+            }))
+            .child(
+                div()
+                    .flex()
+                    .flex_wrap()
+                    .items_center()
+                    .gap_1()
+                    .child(
+                        button("add-stop", "+ Stop")
+                            .on_click(cx.listener(|this, _, _, cx| this.add_gradient_stop(cx))),
+                    )
+                    .child(
+                        button("gradient-css", "CSS")
+                            .on_click(cx.listener(|this, _, _, cx| this.export_gradient_css(cx))),
+                    )
+                    .child(
+                        button("gradient-json", "JSON")
+                            .on_click(cx.listener(|this, _, _, cx| this.export_gradient_json(cx))),
+                    )
+                    .child(div().flex_1())
+                    .child(
+                        button("fewer-steps", "-").on_click(cx.listener(|this, _, _, cx| {
+                            this.gradient_steps =
+                                (this.gradient_steps - 1).max(*GRADIENT_STEPS.start());
+                            cx.notify();
+                        })),
+                    )
+                    .child(format!("{} steps", self.gradient_steps))
+                    .child(
+                        button("more-steps", "+").on_click(cx.listener(|this, _, _, cx| {
+                            this.gradient_steps =
+                                (this.gradient_steps + 1).min(*GRADIENT_STEPS.end());
+                            cx.notify();
+                        })),
+                    )
+                    .child(
+                        button("gradient-palette", "Add to palette").on_click(
+                            cx.listener(|this, _, _, cx| this.add_gradient_to_palette(cx)),
+                        ),
+                    )
+                    .child(
+                        button("gradient-palette-export", "Export").on_click(
+                            cx.listener(|this, _, _, cx| this.export_gradient_palette(cx)),
+                        ),
+                    ),
+            )
+    }
+
+    fn render_blend(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let [a, b] = self.blend_colors;
+        let result = self.blend_result();
+        let steps = blend::steps(a, b, self.blend_mode, self.blend_space, BLEND_STEPS);
+
+        // A, B and the result, each loadable by clicking and copyable by right-click
+        let slot = |id: &'static str, label: &'static str, color: Rgba| {
+            div()
+                .id(id)
+                .flex_1()
+                .flex()
+                .flex_col()
+                .gap_1()
+                .cursor_pointer()
+                .on_click(cx.listener(move |this, _, _, cx| this.set_color(color, cx)))
+                .on_mouse_down(MouseButton::Right, Self::copy_menu_listener(color, cx))
+                .on_hover(Self::swatch_hover_listener(color, cx))
+                .child(
+                    div()
+                        .relative()
+                        .h_10()
+                        .rounded_md()
+                        .overflow_hidden()
+                        .child(checkerboard().absolute().size_full())
+                        .child(
+                            div()
+                                .absolute()
+                                .size_full()
+                                .bg(vision::simulate(self.simulation, color)),
+                        ),
+                )
+                .child(
+                    div()
+                        .text_sm()
+                        .text_color(rgb(0xb0b0b0))
+                        .child(format!("{label} {}", color::to_hex(color))),
+                )
+        };
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .child(
+                div()
+                    .flex()
+                    .justify_between()
+                    .items_center()
+                    .child("Blend")
+                    .child(
+                        div()
+                            .flex()
+                            .gap_1()
+                            .child(button("blend-set-a", "Set A").on_click(cx.listener(
+                                |this, _, _, cx| {
+                                    this.blend_colors[0] = this.color();
+                                    cx.notify();
+                                },
+                            )))
+                            .child(button("blend-set-b", "Set B").on_click(cx.listener(
+                                |this, _, _, cx| {
+                                    this.blend_colors[1] = this.color();
+                                    cx.notify();
+                                },
+                            )))
+                            .child(button("blend-swap", "Swap").on_click(cx.listener(
+                                |this, _, _, cx| {
+                                    this.blend_colors.reverse();
+                                    cx.notify();
+                                },
+                            ))),
+                    ),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_wrap()
+                    .gap_1()
+                    .children(BlendMode::ALL.into_iter().map(|mode| {
+                        button(("blend-mode", mode as usize), mode.label())
+                            .when(mode == self.blend_mode, |this| this.bg(rgb(0x505050)))
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.blend_mode = mode;
+                                cx.notify();
+                            }))
+                    }))
+                    .when(self.blend_mode == BlendMode::Normal, |this| {
+                        this.child(div().w_4())
+                            .children(Interpolation::ALL.into_iter().map(|space| {
+                                button(("blend-space", space as usize), space.label())
+                                    .when(space == self.blend_space, |this| this.bg(rgb(0x505050)))
+                                    .on_click(cx.listener(move |this, _, _, cx| {
+                                        this.blend_space = space;
+                                        cx.notify();
+                                    }))
+                            }))
+                    }),
+            )
+            .child(
+                div()
+                    .flex()
+                    .gap_2()
+                    .child(slot("blend-a", "A", a))
+                    .child(slot("blend-b", "B", b))
+                    .child(slot("blend-result", "=", result)),
+            )
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child("Ratio")
+                    .child(div().flex_1().child(
+                        Slider::new("blend-ratio", self.blend_ratio).on_change(cx.listener(
+                            |this, value, _, cx| {
+                                this.blend_ratio = *value;
+                                cx.notify();
+                            },
+                        )),
+                    ))
+                    .child(
+                        div()
+                            .w_12()
+                            .text_color(rgb(0xb0b0b0))
+                            .child(format!("{:.0}%", self.blend_ratio * 100.0)),
+                    ),
+            )
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_1()
+                    .children(steps.into_iter().enumerate().map(|(index, color)| {
+                        div()
+                            .id(("blend-step", index))
+                            .relative()
+                            .flex_1()
+                            .h_6()
+                            .rounded_sm()
+                            .overflow_hidden()
+                            .cursor_pointer()
+                            .on_click(cx.listener(move |this, _, _, cx| this.set_color(color, cx)))
+                            .on_mouse_down(MouseButton::Right, Self::copy_menu_listener(color, cx))
+                            .on_hover(Self::swatch_hover_listener(color, cx))
+                            .child(checkerboard().absolute().size_full())
+                            .child(
+                                div()
+                                    .absolute()
+                                    .size_full()
+                                    .bg(vision::simulate(self.simulation, color)),
+                            )
+                    }))
+                    .child(button("blend-palette", "+ Add all").on_click(
+                        cx.listener(|this, _, _, cx| this.add_blend_steps_to_palette(cx)),
+                    )),
+            )
+    }
+
+    fn render_scale(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        // Inverse of the curve mapping below, for the slider position
+        let position =
+            (self.scale_curve / SCALE_CURVE_MIN).ln() / (SCALE_CURVE_MAX / SCALE_CURVE_MIN).ln();
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .child("Scale")
+            .child(
+                div()
+                    .flex()
+                    .children(self.scale().steps.into_iter().map(|step| {
+                        let color = step.color;
+                        div()
+                            .id(("scale", step.step as usize))
+                            .flex_1()
+                            .flex()
+                            .flex_col()
+                            .items_center()
+                            .gap_1()
+                            .cursor_pointer()
+                            .on_click(cx.listener(move |this, _, _, cx| this.set_color(color, cx)))
+                            .on_mouse_down(MouseButton::Right, Self::copy_menu_listener(color, cx))
+                            .on_hover(Self::swatch_hover_listener(color, cx))
+                            .child(
+                                div()
+                                    .w_full()
+                                    .h_8()
+                                    .bg(vision::simulate(self.simulation, color)),
+                            )
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(rgb(0xb0b0b0))
+                                    .child(step.step.to_string()),
+                            )
+                    })),
+            )
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child("Curve")
+                    .child(
+                        div()
+                            .flex_1()
+                            .child(Slider::new("scale-curve", position).on_change(cx.listener(
+                                |this, value, _, cx| {
+                                    this.scale_curve = SCALE_CURVE_MIN
+                                        * (SCALE_CURVE_MAX / SCALE_CURVE_MIN).powf(*value);
+                                    cx.notify();
+                                },
+                            ))),
+                    )
+                    .child(
+                        div()
+                            .w_12()
+                            .text_color(rgb(0xb0b0b0))
+                            .child(format!("{:.2}", self.scale_curve)),
+                    ),
+            )
+            .child(
+                div()
+                    .flex()
+                    .gap_1()
+                    .child(
+                        button("scale-palette", "Add to palette")
+                            .on_click(cx.listener(|this, _, _, cx| this.add_scale_to_palette(cx))),
+                    )
+                    .child(
+                        button("scale-css", "CSS")
+                            .on_click(cx.listener(|this, _, _, cx| this.export_scale(false, cx))),
+                    )
+                    .child(
+                        button("scale-tokens", "Tokens")
+                            .on_click(cx.listener(|this, _, _, cx| this.export_scale(true, cx))),
+                    ),
+            )
+    }
+
+    fn render_image(&self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let header = div()
+            .flex()
+            .justify_between()
+            .items_center()
+            .child("Image")
+            .child(
+                button("open-image", "Open…")
+                    .on_click(cx.listener(|this, _, _, cx| this.open_image(cx))),
+            );
+
+        let body = self.image.as_ref().map(|(path, image)| {
+            let scale = (IMAGE_MAX_WIDTH / image.width() as f32)
+                .min(IMAGE_MAX_HEIGHT / image.height() as f32);
+            let entity = cx.entity().downgrade();
+            let eyedropper: picker::PointHandler = Rc::new(move |x, y, _, cx| {
+                entity
+                    .update(cx, |this, cx| this.sample_image(x, y, cx))
+                    .ok();
+            });
+
+            div()
+                .flex()
+                .flex_col()
+                .gap_2()
+                .child(
+                    div()
+                        .relative()
+                        .w(px(image.width() as f32 * scale))
+                        .h(px(image.height() as f32 * scale))
+                        .child(img(path.clone()).size_full())
+                        .child(picker::pointer_surface(
+                            "image".into(),
+                            None,
+                            Some(eyedropper),
+                            window,
+                            cx,
+                        )),
+                )
+                .child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .gap_1()
+                        .child(button("fewer-image-colors", "-").on_click(cx.listener(
+                            |this, _, _, cx| {
+                                this.image_palette_size =
+                                    (this.image_palette_size - 1).max(*IMAGE_PALETTE_SIZES.start());
+                                cx.notify();
+                            },
+                        )))
+                        .child(format!("{} colors", self.image_palette_size))
+                        .child(button("more-image-colors", "+").on_click(cx.listener(
+                            |this, _, _, cx| {
+                                this.image_palette_size =
+                                    (this.image_palette_size + 1).min(*IMAGE_PALETTE_SIZES.end());
+                                cx.notify();
+                            },
+                        )))
+                        .child(
+                            button("extract-palette", "Extract to palette")
+                                .on_click(cx.listener(|this, _, _, cx| this.add_image_palette(cx))),
+                        ),
+                )
+        });
+
+        div().flex().flex_col().gap_2().child(header).children(body)
+    }
+
+    // Closest named color and palette swatch, each with its CIEDE2000 distance
+    fn render_nearest(&self, color: Rgba) -> impl IntoElement + use<> {
+        let (name, distance) = distance::nearest_named(color);
+        // Whichever of the palette's swatches and the theme's tokens is closer
+        let (theme_index, theme_distance) = distance::nearest_theme_color(color);
+        let (theme_name, theme_color) = THEME_COLORS[theme_index];
+        let (label, match_color, match_distance) =
+            match distance::nearest_swatch(&self.palette, color) {
+                Some((index, distance)) if distance <= theme_distance => {
+                    let swatch = &self.palette.swatches[index];
+                    (swatch.name.clone(), swatch.color, distance)
+                }
+                _ => (
+                    format!("theme {theme_name}"),
+                    rgb(theme_color),
+                    theme_distance,
+                ),
+            };
+
         div()
             .flex()
             .flex_col()
+            .items_center()
+            .child(format!("≈ {name} ΔE {distance:.1}"))
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_1()
+                    .child(
+                        div()
+                            .size_3()
+                            .border_1()
+                            .border_color(rgb(0x808080))
+                            .bg(match_color),
+                    )
+                    .child(format!("{label} ΔE {match_distance:.1}")),
+            )
+    }
+
+    // Right-click handler for swatches outside the palette
+    fn copy_menu_listener(
+        color: Rgba,
+        cx: &Context<Self>,
+    ) -> impl Fn(&MouseDownEvent, &mut Window, &mut App) + 'static {
+        cx.listener(move |this, event: &MouseDownEvent, _, cx| {
+            this.open_copy_menu(color, event.position, None, cx)
+        })
+    }
+
+    fn swatch_hover_listener(
+        color: Rgba,
+        cx: &Context<Self>,
+    ) -> impl Fn(&bool, &mut Window, &mut App) + 'static {
+        cx.listener(move |this, hovered: &bool, _, _| {
+            if *hovered {
+                this.hovered_swatch = Some(color);
+            } else if this.hovered_swatch == Some(color) {
+                this.hovered_swatch = None;
+            }
+        })
+    }
+
+    fn render_copy_menu(&self, cx: &mut Context<Self>) -> Option<impl IntoElement + use<>> {
+        let menu = self.copy_menu.as_ref()?;
+        let color = menu.color;
+        let item = |id: ElementId| {
+            div()
+                .id(id)
+                .flex()
+                .justify_between()
+                .gap_4()
+                .px_2()
+                .py_1()
+                .rounded_sm()
+                .hover(|style| style.bg(rgb(0x404040)))
+                .cursor_pointer()
+        };
+
+        let formats = ColorFormat::ALL.into_iter().map(|format| {
+            item(format.id().into())
+                .child(format.label())
+                .child(div().text_color(rgb(0xb0b0b0)).child(format.format(color)))
+                .on_click(cx.listener(move |this, _, _, cx| this.copy(color, format, cx)))
+        });
+        let remove = menu.swatch.map(|index| {
+            item("remove-swatch".into())
+                .border_t_1()
+                .border_color(rgb(0x505050))
+                .child("Remove from palette")
+                .on_click(cx.listener(move |this, _, _, cx| {
+                    this.copy_menu = None;
+                    this.remove_from_palette(index, cx);
+                }))
+        });
+
+        Some(
+            deferred(
+                anchored().position(menu.position).snap_to_window().child(
+                    div()
+                        .id("copy-menu")
+                        .occlude()
+                        .flex()
+                        .flex_col()
+                        .p_1()
+                        .min_w(px(280.0))
+                        .rounded_md()
+                        .border_1()
+                        .border_color(rgb(0x505050))
+                        .bg(rgb(0x252525))
+                        .shadow_lg()
+                        .text_sm()
+                        .on_mouse_down_out(cx.listener(|this, _, _, cx| this.close_copy_menu(cx)))
+                        .children(formats)
+                        .children(remove),
+                ),
+            )
+            .with_priority(1),
+        )
+    }
+
+    fn render_history(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .flex()
+            .items_center()
+            .gap_1()
+            .child(div().text_sm().text_color(rgb(0xb0b0b0)).child("History"))
+            .children(
+                self.history
+                    .recent()
+                    .take(HISTORY_STRIP_LEN)
+                    .enumerate()
+                    .map(|(index, color)| {
+                        div()
+                            .id(("history", index))
+                            .size_5()
+                            .rounded_sm()
+                            .border_1()
+                            .border_color(rgb(0x505050))
+                            .bg(vision::simulate(self.simulation, color))
+                            .cursor_pointer()
+                            .on_click(cx.listener(move |this, _, _, cx| this.revert_to(index, cx)))
+                            .on_mouse_down(MouseButton::Right, Self::copy_menu_listener(color, cx))
+                            .on_hover(Self::swatch_hover_listener(color, cx))
+                    }),
+            )
+            .child(div().flex_1())
+            .child(
+                button("undo", "Undo")
+                    .on_click(cx.listener(|this, _, window, cx| this.undo(&Undo, window, cx))),
+            )
+            .child(
+                button("redo", "Redo")
+                    .on_click(cx.listener(|this, _, window, cx| this.redo(&Redo, window, cx))),
+            )
+    }
+
+    fn render_mode_switcher(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .flex()
             .gap_1()
-            .child(label)
-            .child(div().flex().gap_1().children((0..20).map(|i| {
-                let v = (i as f32) / 19.0;
-                let active = v <= value;
-                div()
-                    .id((label, i as usize))
-                    .w_4()
-                    .h_6()
-                    .bg(if active { rgb(0xffffff) } else { rgb(0x505050) })
+            .children(ColorMode::ALL.into_iter().map(|mode| {
+                let active = mode == self.mode;
+                div()
+                    .id(mode.label())
+                    .px_3()
+                    .py_1()
+                    .rounded_md()
+                    .bg(if active { rgb(0x505050) } else { rgb(0x252525) })
+                    .hover(|style| style.bg(rgb(0x404040)))
                     .cursor_pointer()
                     .on_click(cx.listener(move |this, _, _, cx| {
-                        setter(this, v, cx);
+                        this.set_mode(mode, cx);
                     }))
-            })))
+                    .child(mode.label())
+            }))
+    }
+
+    fn step_listener<A: Action>(
+        index: usize,
+        steps: f32,
+        cx: &mut Context<Self>,
+    ) -> impl Fn(&A, &mut Window, &mut App) + 'static {
+        cx.listener(move |this, _: &A, _, cx| this.step_channel(index, steps, cx))
+    }
+
+    fn render_channel_control(
+        &self,
+        index: usize,
+        label: &'static str,
+        readout: String,
+        window: &Window,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement + use<> {
+        let focus_handle = &self.channel_focus[index];
+
+        div()
+            .id(("channel", index))
+            .key_context("ChannelRow")
+            .track_focus(focus_handle)
+            .on_action::<Increment>(Self::step_listener(index, 1.0, cx))
+            .on_action::<Decrement>(Self::step_listener(index, -1.0, cx))
+            .on_action::<IncrementCoarse>(Self::step_listener(index, COARSE_STEPS, cx))
+            .on_action::<DecrementCoarse>(Self::step_listener(index, -COARSE_STEPS, cx))
+            .on_action(cx.listener(move |this, _: &SetMinimum, _, cx| {
+                this.set_channel(index, 0.0, false, cx)
+            }))
+            .on_action(cx.listener(move |this, _: &SetMaximum, _, cx| {
+                this.set_channel(index, 1.0, false, cx)
+            }))
+            .flex()
+            .flex_col()
+            .gap_1()
+            .p_1()
+            .rounded_md()
+            .border_1()
+            .border_color(if focus_handle.is_focused(window) {
+                rgb(0x64b5f6)
+            } else {
+                rgb(0x303030)
+            })
+            .child(
+                div()
+                    .flex()
+                    .justify_between()
+                    .child(label)
+                    .child(div().text_color(rgb(0xb0b0b0)).child(readout)),
+            )
+            .child(
+                Slider::new(label, self.channel(index)).on_change(cx.listener(
+                    move |this, value, _, cx| {
+                        this.set_channel(index, *value, true, cx);
+                    },
+                )),
+            )
+    }
+}
+
+impl Focusable for ColorMixer {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
     }
 }
 
 impl Render for ColorMixer {
-    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        let color = rgb(((self.red * 255.0) as u32) << 16
-            | ((self.green * 255.0) as u32) << 8
-            | ((self.blue * 255.0) as u32));
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let color = self.color();
+        let hsla = Hsla::from(color);
+        let channels = self.mode.decompose(color);
+        let (text, background) = self.contrast_pair();
 
         div()
+            .key_context("ColorMixer")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::undo))
+            .on_action(cx.listener(Self::redo))
+            .on_action(cx.listener(|_, _: &FocusNext, window, _| window.focus_next()))
+            .on_action(cx.listener(|_, _: &FocusPrevious, window, _| window.focus_prev()))
+            .on_action(cx.listener(Self::copy_color))
+            .relative()
             .flex()
             .flex_col()
             .size_full()
@@ -76,53 +1786,179 @@ impl Render for ColorMixer {
             .child(
                 div()
                     .flex()
-                    .size_full()
-                    .bg(color)
-                    .justify_center()
-                    .items_center()
-                    .child(format!(
-                        "R: {:.2} G: {:.2} B: {:.2}",
-                        self.red, self.green, self.blue
-                    )),
+                    .flex_none()
+                    .h(px(216.0))
+                    .child(self.render_picker(cx))
+                    .child(
+                        div()
+                            .relative()
+                            .size_full()
+                            .child(checkerboard().absolute().size_full())
+                            .child(
+                                div()
+                                    .absolute()
+                                    .size_full()
+                                    .flex()
+                                    .flex_col()
+                                    .bg(vision::simulate(self.simulation, background))
+                                    .text_color(vision::simulate(self.simulation, text))
+                                    .justify_center()
+                                    .items_center()
+                                    .on_mouse_down(
+                                        MouseButton::Right,
+                                        cx.listener(|this, event: &MouseDownEvent, _, cx| {
+                                            this.open_copy_menu(
+                                                this.color(),
+                                                event.position,
+                                                None,
+                                                cx,
+                                            )
+                                        }),
+                                    )
+                                    .child(format!(
+                                        "R: {:.2} G: {:.2} B: {:.2} A: {:.2}",
+                                        color.r, color.g, color.b, color.a
+                                    ))
+                                    .child(format!(
+                                        "H: {:.0}° S: {:.0}% L: {:.0}% A: {:.2}",
+                                        hsla.h * 360.0,
+                                        hsla.s * 100.0,
+                                        hsla.l * 100.0,
+                                        hsla.a
+                                    ))
+                                    .child(self.render_nearest(color)),
+                            )
+                            .child(div().absolute().top_2().right_2().child(
+                                button("copy", "Copy").on_click(cx.listener(
+                                    |this, event: &ClickEvent, _, cx| {
+                                        this.open_copy_menu(
+                                            this.color(),
+                                            event.position(),
+                                            None,
+                                            cx,
+                                        )
+                                    },
+                                )),
+                            )),
+                    )
+                    .child(self.render_swatch_strip(cx)),
             )
             .child(
                 div()
+                    .id("controls")
                     .flex()
                     .flex_col()
+                    .flex_1()
+                    .min_h(px(0.0))
+                    .overflow_y_scroll()
                     .p_4()
                     .gap_4()
                     .bg(rgb(0x303030))
+                    .child(self.input.clone())
+                    .child(self.render_history(cx))
+                    .child(self.render_mode_switcher(cx))
+                    .child(self.render_vision_switcher(cx))
+                    .children(
+                        self.mode
+                            .channel_labels()
+                            .into_iter()
+                            .enumerate()
+                            .map(|(index, label)| {
+                                self.render_channel_control(
+                                    index,
+                                    label,
+                                    self.mode.format_channel(index, channels[index]),
+                                    window,
+                                    cx,
+                                )
+                            })
+                            .collect::<Vec<_>>(),
+                    )
                     .child(self.render_channel_control(
-                        "Red",
-                        self.red,
-                        |this, val, cx| this.set_red(val, cx),
-                        cx,
-                    ))
-                    .child(self.render_channel_control(
-                        "Green",
-                        self.green,
-                        |this, val, cx| this.set_green(val, cx),
+                        ALPHA_CHANNEL,
+                        "Alpha",
+                        format!("{:.0}%", self.alpha * 100.0),
+                        window,
                         cx,
                     ))
-                    .child(self.render_channel_control(
-                        "Blue",
-                        self.blue,
-                        |this, val, cx| this.set_blue(val, cx),
-                        cx,
-                    )),
+                    .child(self.render_contrast(cx))
+                    .child(self.render_harmonies(cx))
+                    .child(self.render_gradient(cx))
+                    .child(self.render_blend(cx))
+                    .child(self.render_scale(cx))
+                    .child(self.render_image(window, cx))
+                    .child(self.render_palette(cx)),
             )
+            .children(self.toast.clone().map(|toast| {
+                div()
+                    .absolute()
+                    .bottom_4()
+                    .left_0()
+                    .right_0()
+                    .flex()
+                    .justify_center()
+                    .child(
+                        div()
+                            .px_3()
+                            .py_1()
+                            .rounded_md()
+                            .bg(rgb(0x1976d2))
+                            .shadow_lg()
+                            .child(toast),
+                    )
+            }))
+            .children(self.render_copy_menu(cx))
     }
 }
 
+fn button(id: impl Into<ElementId>, label: impl Into<SharedString>) -> Stateful<Div> {
+    div()
+        .id(id)
+        .px_3()
+        .py_1()
+        .rounded_md()
+        .bg(rgb(0x252525))
+        .hover(|style| style.bg(rgb(0x404040)))
+        .cursor_pointer()
+        .child(label.into())
+}
+
 fn main() {
+    // Any arguments select a headless subcommand instead of the window
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        std::process::exit(cli::main(&args));
+    }
+
     Application::new().run(|cx: &mut App| {
-        let bounds = Bounds::centered(None, size(px(500.), px(500.0)), cx);
+        // `secondary` is cmd on macOS and ctrl elsewhere
+        cx.bind_keys([
+            KeyBinding::new("secondary-z", Undo, Some("ColorMixer")),
+            KeyBinding::new("secondary-shift-z", Redo, Some("ColorMixer")),
+            KeyBinding::new("tab", FocusNext, Some("ColorMixer")),
+            KeyBinding::new("shift-tab", FocusPrevious, Some("ColorMixer")),
+            KeyBinding::new("secondary-c", CopyColor, Some("ColorMixer")),
+            KeyBinding::new("right", Increment, Some("ChannelRow")),
+            KeyBinding::new("up", Increment, Some("ChannelRow")),
+            KeyBinding::new("left", Decrement, Some("ChannelRow")),
+            KeyBinding::new("down", Decrement, Some("ChannelRow")),
+            KeyBinding::new("pageup", IncrementCoarse, Some("ChannelRow")),
+            KeyBinding::new("pagedown", DecrementCoarse, Some("ChannelRow")),
+            KeyBinding::new("home", SetMinimum, Some("ChannelRow")),
+            KeyBinding::new("end", SetMaximum, Some("ChannelRow")),
+        ]);
+
+        let bounds = Bounds::centered(None, size(px(560.0), px(900.0)), cx);
         cx.open_window(
             WindowOptions {
                 window_bounds: Some(WindowBounds::Windowed(bounds)),
                 ..Default::default()
             },
-            |_, cx| cx.new(|cx| ColorMixer::new(cx)),
+            |window, cx| {
+                let mixer = cx.new(ColorMixer::new);
+                mixer.focus_handle(cx).focus(window);
+                mixer
+            },
         )
         .unwrap();
         cx.activate(true);
@@ -0,0 +1,319 @@
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use gpui::Rgba;
+
+use super::{JsonPalette, JsonSwatch, Palette};
+use crate::color::to_hex;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    Css,
+    Gpl,
+    Ase,
+    Json,
+    Rust,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 5] = [
+        ExportFormat::Css,
+        ExportFormat::Gpl,
+        ExportFormat::Ase,
+        ExportFormat::Json,
+        ExportFormat::Rust,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ExportFormat::Css => "CSS",
+            ExportFormat::Gpl => "GIMP",
+            ExportFormat::Ase => "ASE",
+            ExportFormat::Json => "JSON",
+            ExportFormat::Rust => "Rust",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Css => "css",
+            ExportFormat::Gpl => "gpl",
+            ExportFormat::Ase => "ase",
+            ExportFormat::Json => "json",
+            ExportFormat::Rust => "rs",
+        }
+    }
+
+    pub fn export(self, palette: &Palette) -> Vec<u8> {
+        match self {
+            ExportFormat::Css => to_css(palette).into_bytes(),
+            ExportFormat::Gpl => to_gpl(palette).into_bytes(),
+            ExportFormat::Ase => to_ase(palette),
+            ExportFormat::Json => to_json(palette).into_bytes(),
+            ExportFormat::Rust => to_rust(palette).into_bytes(),
+        }
+    }
+}
+
+/// CSS custom properties on `:root`
+pub fn to_css(palette: &Palette) -> String {
+    let mut css = format!("/* {} */\n:root {{\n", palette.name);
+    for (swatch, name) in palette.swatches.iter().zip(identifiers(palette, "-")) {
+        writeln!(
+            css,
+            "  --{}: {};",
+            name.to_lowercase(),
+            to_hex(swatch.color)
+        )
+        .unwrap();
+    }
+    css.push_str("}\n");
+    css
+}
+
+/// GIMP palette; alpha is not representable and is dropped
+pub fn to_gpl(palette: &Palette) -> String {
+    let mut gpl = format!("GIMP Palette\nName: {}\nColumns: 0\n#\n", palette.name);
+    for swatch in &palette.swatches {
+        let [r, g, b, _] = bytes(swatch.color);
+        writeln!(gpl, "{r:3} {g:3} {b:3}\t{}", swatch.name).unwrap();
+    }
+    gpl
+}
+
+/// Adobe Swatch Exchange (version 1.0, big-endian), one global RGB entry per swatch
+pub fn to_ase(palette: &Palette) -> Vec<u8> {
+    const COLOR_ENTRY: u16 = 0x0001;
+    const GLOBAL_COLOR: u16 = 0;
+
+    let mut ase = Vec::new();
+    ase.extend_from_slice(b"ASEF");
+    ase.extend_from_slice(&1u16.to_be_bytes());
+    ase.extend_from_slice(&0u16.to_be_bytes());
+    ase.extend_from_slice(&(palette.swatches.len() as u32).to_be_bytes());
+
+    for swatch in &palette.swatches {
+        // Names are null-terminated UTF-16
+        let name: Vec<u16> = swatch.name.encode_utf16().chain([0]).collect();
+
+        let mut block = Vec::new();
+        block.extend_from_slice(&(name.len() as u16).to_be_bytes());
+        for unit in name {
+            block.extend_from_slice(&unit.to_be_bytes());
+        }
+        block.extend_from_slice(b"RGB ");
+        for channel in [swatch.color.r, swatch.color.g, swatch.color.b] {
+            block.extend_from_slice(&channel.to_be_bytes());
+        }
+        block.extend_from_slice(&GLOBAL_COLOR.to_be_bytes());
+
+        ase.extend_from_slice(&COLOR_ENTRY.to_be_bytes());
+        ase.extend_from_slice(&(block.len() as u32).to_be_bytes());
+        ase.extend_from_slice(&block);
+    }
+    ase
+}
+
+pub fn to_json(palette: &Palette) -> String {
+    let json = JsonPalette {
+        name: palette.name.clone(),
+        colors: palette
+            .swatches
+            .iter()
+            .map(|swatch| JsonSwatch {
+                name: swatch.name.clone(),
+                hex: to_hex(swatch.color),
+            })
+            .collect(),
+    };
+    let mut text = serde_json::to_string_pretty(&json).expect("palette is always serializable");
+    text.push('\n');
+    text
+}
+
+/// A Rust module of hex constants for use with `gpui::rgb`/`gpui::rgba`,
+/// plus accessor functions in the style of `Theme`
+pub fn to_rust(palette: &Palette) -> String {
+    let names = identifiers(palette, "_");
+    let mut rust = format!(
+        "// Generated by color-mixer from the \"{}\" palette\n\nuse gpui::{{Rgba, rgb, rgba}};\n\n",
+        palette.name
+    );
+
+    for (swatch, name) in palette.swatches.iter().zip(&names) {
+        writeln!(
+            rust,
+            "pub const {}: u32 = {}; // {}",
+            name.to_uppercase(),
+            hex(swatch.color),
+            swatch.name
+        )
+        .unwrap();
+    }
+
+    for (swatch, name) in palette.swatches.iter().zip(&names) {
+        let constructor = if swatch.color.a < 1.0 { "rgba" } else { "rgb" };
+        let mut function = name.to_lowercase();
+        // Identifiers never end in `_`, so this can't clash with another swatch
+        if RUST_KEYWORDS.contains(&function.as_str()) {
+            function.push('_');
+        }
+        write!(
+            rust,
+            "\npub fn {function}() -> Rgba {{\n    {constructor}({}) // {}\n}}\n",
+            hex(swatch.color),
+            swatch.name
+        )
+        .unwrap();
+    }
+    rust
+}
+
+// Strict and reserved keywords, which can't name a function. `r#` doesn't
+// work for all of them (`self`, `super`, `crate`), so a trailing `_` is used.
+const RUST_KEYWORDS: [&str; 51] = [
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof",
+    "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+// `0xRRGGBB`, or `0xRRGGBBAA` when the color isn't opaque
+fn hex(color: Rgba) -> String {
+    let [r, g, b, a] = bytes(color);
+    if a == 255 {
+        format!("0x{r:02X}{g:02X}{b:02X}")
+    } else {
+        format!("0x{r:02X}{g:02X}{b:02X}{a:02X}")
+    }
+}
+
+fn bytes(color: Rgba) -> [u8; 4] {
+    [color.r, color.g, color.b, color.a].map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8)
+}
+
+// Turns swatch names into unique identifiers made of ASCII words joined by
+// `separator`, e.g. "Accent Hover" -> "Accent_Hover"
+fn identifiers(palette: &Palette, separator: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    palette
+        .swatches
+        .iter()
+        .enumerate()
+        .map(|(index, swatch)| {
+            let words: Vec<&str> = swatch
+                .name
+                .split(|c: char| !c.is_ascii_alphanumeric())
+                .filter(|word| !word.is_empty())
+                .collect();
+            let mut name = words.join(separator);
+            if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+                name = format!("color{separator}{name}");
+            }
+            while !seen.insert(name.to_lowercase()) {
+                name = format!("{name}{separator}{}", index + 1);
+            }
+            name
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::{rgb, rgba};
+
+    use super::*;
+    use crate::palette::Swatch;
+
+    fn brand() -> Palette {
+        Palette {
+            name: "Brand".into(),
+            swatches: vec![
+                Swatch {
+                    name: "Primary".into(),
+                    color: rgb(0x1976D2),
+                },
+                Swatch {
+                    name: "Accent Hover".into(),
+                    color: rgb(0x1565C0),
+                },
+                Swatch {
+                    name: "Overlay".into(),
+                    color: rgba(0x00000080),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn css_matches_golden_file() {
+        assert_eq!(
+            to_css(&brand()),
+            include_str!("../../tests/golden/brand.css")
+        );
+    }
+
+    #[test]
+    fn gpl_matches_golden_file() {
+        assert_eq!(
+            to_gpl(&brand()),
+            include_str!("../../tests/golden/brand.gpl")
+        );
+    }
+
+    #[test]
+    fn ase_matches_golden_file() {
+        assert_eq!(
+            to_ase(&brand()),
+            include_bytes!("../../tests/golden/brand.ase")
+        );
+    }
+
+    #[test]
+    fn json_matches_golden_file() {
+        assert_eq!(
+            to_json(&brand()),
+            include_str!("../../tests/golden/brand.json")
+        );
+    }
+
+    #[test]
+    fn rust_matches_golden_file() {
+        assert_eq!(
+            to_rust(&brand()),
+            include_str!("../../tests/golden/brand.rs")
+        );
+    }
+
+    #[test]
+    fn rust_keywords_and_digits_match_golden_file() {
+        let mut palette = Palette::new("Keywords");
+        for (name, color) in [
+            ("type", 0x000000),
+            ("Match", 0x808080),
+            ("self", 0xffffff),
+            ("3 tone", 0xff9800),
+        ] {
+            palette.add(rgb(color));
+            palette.swatches.last_mut().unwrap().name = name.into();
+        }
+        assert_eq!(
+            to_rust(&palette),
+            include_str!("../../tests/golden/keywords.rs")
+        );
+    }
+
+    #[test]
+    fn identifiers_are_unique_and_valid() {
+        let mut palette = Palette::new("Clashes");
+        palette.add(rgb(0x000000));
+        palette.add(rgb(0xffffff));
+        palette.swatches[0].name = "1 dark".into();
+        palette.swatches[1].name = "color-1 dark!".into();
+        assert_eq!(
+            identifiers(&palette, "_"),
+            ["color_1_dark", "color_1_dark_2"]
+        );
+    }
+}
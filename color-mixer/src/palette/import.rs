@@ -0,0 +1,475 @@
+use std::fmt;
+use std::path::Path;
+
+use gpui::Rgba;
+
+use super::{JsonPalette, Palette, Swatch};
+use crate::color::Lab;
+use crate::parse::parse_color;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ImportError {
+    Io(String),
+    UnsupportedFormat(String),
+    // Text formats report a 1-based line number
+    Syntax { line: usize, message: String },
+    // Binary formats report a byte offset
+    Binary { offset: usize, message: String },
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ImportError::Io(message) => write!(f, "{message}"),
+            ImportError::UnsupportedFormat(extension) => {
+                write!(f, "unsupported palette format \"{extension}\"")
+            }
+            ImportError::Syntax { line, message } => write!(f, "line {line}: {message}"),
+            ImportError::Binary { offset, message } => write!(f, "byte {offset}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+fn syntax(line: usize, message: impl Into<String>) -> ImportError {
+    ImportError::Syntax {
+        line,
+        message: message.into(),
+    }
+}
+
+/// Reads a palette, choosing the parser from the file extension
+pub fn import(path: &Path) -> Result<Palette, ImportError> {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let bytes = std::fs::read(path).map_err(|error| ImportError::Io(error.to_string()))?;
+    let text = || String::from_utf8_lossy(&bytes).into_owned();
+
+    let mut palette = match extension.as_str() {
+        "gpl" => from_gpl(&text())?,
+        "ase" => from_ase(&bytes)?,
+        "json" => from_json(&text())?,
+        "css" => from_css(&text())?,
+        _ => return Err(ImportError::UnsupportedFormat(extension)),
+    };
+    // Formats without an embedded name take the file name
+    if palette.name.is_empty() {
+        palette.name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+    }
+    Ok(palette)
+}
+
+/// GIMP palette: a `GIMP Palette` header, optional `Name:`/`Columns:` lines,
+/// `#` comments and `r g b [name]` rows
+pub fn from_gpl(text: &str) -> Result<Palette, ImportError> {
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()));
+    match lines.next() {
+        Some((_, "GIMP Palette")) => {}
+        _ => return Err(syntax(1, "missing \"GIMP Palette\" header")),
+    }
+
+    let mut palette = Palette::new("");
+    for (line_number, line) in lines {
+        if line.is_empty() || line.starts_with('#') || line.starts_with("Columns:") {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix("Name:") {
+            palette.name = name.trim().to_string();
+            continue;
+        }
+
+        let mut fields = line.split_whitespace();
+        let mut channel = |label: &str| {
+            let field = fields
+                .next()
+                .ok_or_else(|| syntax(line_number, format!("missing {label} value")))?;
+            field
+                .parse::<u8>()
+                .map(|value| value as f32 / 255.0)
+                .map_err(|_| syntax(line_number, format!("invalid {label} value \"{field}\"")))
+        };
+        let color = Rgba {
+            r: channel("red")?,
+            g: channel("green")?,
+            b: channel("blue")?,
+            a: 1.0,
+        };
+        let name = fields.collect::<Vec<_>>().join(" ");
+        push(&mut palette, name, color);
+    }
+    Ok(palette)
+}
+
+/// Adobe Swatch Exchange; groups are flattened and RGB, CMYK, LAB and Gray
+/// entries are converted to sRGB
+pub fn from_ase(bytes: &[u8]) -> Result<Palette, ImportError> {
+    const GROUP_START: u16 = 0xc001;
+    const GROUP_END: u16 = 0xc002;
+    const COLOR_ENTRY: u16 = 0x0001;
+
+    let mut reader = Reader { bytes, offset: 0 };
+    if reader.take(4)? != b"ASEF" {
+        return Err(reader.error(0, "missing \"ASEF\" signature"));
+    }
+    let major = reader.u16()?;
+    reader.u16()?;
+    if major != 1 {
+        return Err(reader.error(4, format!("unsupported version {major}")));
+    }
+
+    let mut palette = Palette::new("");
+    let block_count = reader.u32()?;
+    for _ in 0..block_count {
+        let block_start = reader.offset;
+        let block_type = reader.u16()?;
+        let length = reader.u32()? as usize;
+        let body_start = reader.offset;
+        let mut block = Reader {
+            bytes: reader.take(length)?,
+            offset: 0,
+        };
+
+        match block_type {
+            COLOR_ENTRY => {
+                let at = |offset| body_start + offset;
+                let name = block.utf16_name().map_err(|e| e.shifted(body_start))?;
+                let model_offset = block.offset;
+                let model = block.take(4).map_err(|e| e.shifted(body_start))?;
+                let mut values = |count: usize| {
+                    (0..count)
+                        .map(|_| block.f32())
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(|e| e.shifted(body_start))
+                };
+                let color = match model {
+                    b"RGB " => {
+                        let v = values(3)?;
+                        Rgba {
+                            r: v[0],
+                            g: v[1],
+                            b: v[2],
+                            a: 1.0,
+                        }
+                    }
+                    b"CMYK" => {
+                        let v = values(4)?;
+                        let k = 1.0 - v[3];
+                        Rgba {
+                            r: (1.0 - v[0]) * k,
+                            g: (1.0 - v[1]) * k,
+                            b: (1.0 - v[2]) * k,
+                            a: 1.0,
+                        }
+                    }
+                    // ASE stores LAB lightness as 0..1; the white point is treated as D65
+                    b"LAB " => {
+                        let v = values(3)?;
+                        Lab {
+                            l: v[0] * 100.0,
+                            a: v[1],
+                            b: v[2],
+                        }
+                        .to_rgb(1.0)
+                    }
+                    b"Gray" => {
+                        let v = values(1)?;
+                        Rgba {
+                            r: v[0],
+                            g: v[0],
+                            b: v[0],
+                            a: 1.0,
+                        }
+                    }
+                    _ => {
+                        return Err(reader.error(
+                            at(model_offset),
+                            format!("unknown color model \"{}\"", String::from_utf8_lossy(model)),
+                        ));
+                    }
+                };
+                push(&mut palette, name, crate::color::clamp_rgb(color));
+            }
+            GROUP_START => {
+                if palette.name.is_empty() {
+                    palette.name = block.utf16_name().map_err(|e| e.shifted(body_start))?;
+                }
+            }
+            GROUP_END => {}
+            _ => {
+                return Err(
+                    reader.error(block_start, format!("unknown block type {block_type:#06x}"))
+                );
+            }
+        }
+    }
+    Ok(palette)
+}
+
+/// The JSON layout written by the exporter; `name` fields are optional and
+/// colors may use any syntax `parse_color` accepts
+pub fn from_json(text: &str) -> Result<Palette, ImportError> {
+    let json: JsonPalette =
+        serde_json::from_str(text).map_err(|error| syntax(error.line(), error.to_string()))?;
+
+    let mut palette = Palette::new(json.name);
+    for swatch in json.colors {
+        let color = parse_color(&swatch.hex).map_err(|error| {
+            // serde doesn't track value positions, so locate the string ourselves
+            let line = text
+                .find(&format!("\"{}\"", swatch.hex))
+                .map_or(1, |offset| line_of(text, offset));
+            syntax(line, error.to_string())
+        })?;
+        push(&mut palette, swatch.name, color);
+    }
+    Ok(palette)
+}
+
+/// Collects every custom property (`--name: value`) whose value is a color.
+/// Properties holding lengths, `var()` references and the like are skipped.
+pub fn from_css(text: &str) -> Result<Palette, ImportError> {
+    let mut palette = Palette::new("");
+    let mut rest = text;
+    while let Some(start) = rest.find("--") {
+        let offset = text.len() - rest.len() + start;
+        rest = &rest[start + 2..];
+
+        let Some((name, after_name)) = rest.split_once(':') else {
+            break;
+        };
+        // CSS allows whitespace between the name and the colon
+        let name = name.trim_end();
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        {
+            continue;
+        }
+        let end = after_name.find([';', '}']).unwrap_or(after_name.len());
+        if let Ok(color) = parse_color(&after_name[..end]) {
+            push(&mut palette, name.to_string(), color);
+        }
+        rest = &after_name[end..];
+
+        if palette.swatches.is_empty() && rest.is_empty() {
+            return Err(syntax(
+                line_of(text, offset),
+                "no color custom properties found",
+            ));
+        }
+    }
+
+    if palette.swatches.is_empty() {
+        return Err(syntax(1, "no color custom properties found"));
+    }
+    Ok(palette)
+}
+
+fn push(palette: &mut Palette, name: String, color: Rgba) {
+    if name.is_empty() {
+        palette.add(color);
+    } else {
+        palette.swatches.push(Swatch { name, color });
+    }
+}
+
+fn line_of(text: &str, offset: usize) -> usize {
+    text[..offset].matches('\n').count() + 1
+}
+
+// Big-endian cursor over a byte slice that reports its position on failure
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn error(&self, offset: usize, message: impl Into<String>) -> ImportError {
+        ImportError::Binary {
+            offset,
+            message: message.into(),
+        }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], ImportError> {
+        let bytes = self
+            .bytes
+            .get(self.offset..self.offset + len)
+            .ok_or_else(|| self.error(self.offset, "unexpected end of file"))?;
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    fn u16(&mut self) -> Result<u16, ImportError> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, ImportError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn f32(&mut self) -> Result<f32, ImportError> {
+        Ok(f32::from_bits(self.u32()?))
+    }
+
+    // Length-prefixed, null-terminated UTF-16 string
+    fn utf16_name(&mut self) -> Result<String, ImportError> {
+        let len = self.u16()? as usize;
+        let units = (0..len)
+            .map(|_| self.u16())
+            .collect::<Result<Vec<_>, _>>()?;
+        let name = units.split(|&unit| unit == 0).next().unwrap_or_default();
+        Ok(String::from_utf16_lossy(name))
+    }
+}
+
+impl ImportError {
+    // Converts a block-relative offset into a file offset
+    fn shifted(self, base: usize) -> Self {
+        match self {
+            ImportError::Binary { offset, message } => ImportError::Binary {
+                offset: base + offset,
+                message,
+            },
+            other => other,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::to_hex;
+    use crate::palette::export::{to_ase, to_css, to_gpl, to_json};
+
+    fn hexes(palette: &Palette) -> Vec<(String, String)> {
+        palette
+            .swatches
+            .iter()
+            .map(|swatch| (swatch.name.clone(), to_hex(swatch.color)))
+            .collect()
+    }
+
+    fn brand() -> Palette {
+        let mut palette = Palette::new("Brand");
+        palette.swatches.push(Swatch {
+            name: "Primary".into(),
+            color: gpui::rgb(0x1976d2),
+        });
+        palette.swatches.push(Swatch {
+            name: "Accent Hover".into(),
+            color: gpui::rgb(0x1565c0),
+        });
+        palette
+    }
+
+    #[test]
+    fn round_trips_through_every_format() {
+        let expected = hexes(&brand());
+        assert_eq!(hexes(&from_gpl(&to_gpl(&brand())).unwrap()), expected);
+        assert_eq!(hexes(&from_ase(&to_ase(&brand())).unwrap()), expected);
+        assert_eq!(hexes(&from_json(&to_json(&brand())).unwrap()), expected);
+
+        // CSS identifiers are lowercased on export
+        let css = from_css(&to_css(&brand())).unwrap();
+        assert_eq!(
+            hexes(&css),
+            [
+                ("primary".to_string(), "#1976d2".to_string()),
+                ("accent-hover".to_string(), "#1565c0".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn gpl_tolerates_loose_formatting() {
+        let text =
+            "GIMP Palette\r\nName: Loose\r\n\r\n# comment\n0 0 0\n  255\t255  255   Pure white \n";
+        let palette = from_gpl(text).unwrap();
+        assert_eq!(palette.name, "Loose");
+        assert_eq!(
+            hexes(&palette),
+            [
+                ("Color 1".to_string(), "#000000".to_string()),
+                ("Pure white".to_string(), "#ffffff".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn gpl_reports_line_numbers() {
+        assert_eq!(
+            from_gpl("GIMP Palette\n0 0 0\n12 300 4 Bad\n"),
+            Err(syntax(3, "invalid green value \"300\""))
+        );
+        assert_eq!(
+            from_gpl("Not a palette"),
+            Err(syntax(1, "missing \"GIMP Palette\" header"))
+        );
+    }
+
+    #[test]
+    fn ase_reports_offsets() {
+        let ase = to_ase(&brand());
+        assert!(matches!(
+            from_ase(&ase[..ase.len() - 3]),
+            Err(ImportError::Binary { .. })
+        ));
+        assert_eq!(
+            from_ase(b"ASEX\0\x01\0\0\0\0\0\0"),
+            Err(ImportError::Binary {
+                offset: 0,
+                message: "missing \"ASEF\" signature".into()
+            })
+        );
+    }
+
+    #[test]
+    fn json_reports_line_numbers() {
+        let text = "{\n  \"colors\": [\n    { \"hex\": \"#12345\" }\n  ]\n}";
+        assert_eq!(
+            from_json(text),
+            Err(syntax(3, "\"#12345\" is not #rgb, #rrggbb or #rrggbbaa"))
+        );
+        assert!(matches!(
+            from_json("{\n  \"colors\": [\n"),
+            Err(ImportError::Syntax { line: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn css_skips_non_color_properties() {
+        let text = ":root {\n  --gap: 4px;\n  --brand: rebeccapurple;\n  --alias: var(--brand);\n}";
+        let palette = from_css(text).unwrap();
+        assert_eq!(
+            hexes(&palette),
+            [("brand".to_string(), "#663399".to_string())]
+        );
+        assert!(from_css(":root { --gap: 4px; }").is_err());
+    }
+
+    #[test]
+    fn css_allows_space_before_the_colon() {
+        let palette = from_css(":root { --brand : #fff; --accent\t:#1976d2 }").unwrap();
+        assert_eq!(
+            hexes(&palette),
+            [
+                ("brand".to_string(), "#ffffff".to_string()),
+                ("accent".to_string(), "#1976d2".to_string())
+            ]
+        );
+    }
+}
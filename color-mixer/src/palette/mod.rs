@@ -0,0 +1,54 @@
+use gpui::Rgba;
+use serde::{Deserialize, Serialize};
+
+pub mod export;
+pub mod import;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Swatch {
+    pub name: String,
+    pub color: Rgba,
+}
+
+/// An ordered, named collection of swatches
+#[derive(Clone, Debug, PartialEq)]
+pub struct Palette {
+    pub name: String,
+    pub swatches: Vec<Swatch>,
+}
+
+impl Palette {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            swatches: Vec::new(),
+        }
+    }
+
+    /// Appends a color, naming it after its position in the palette
+    pub fn add(&mut self, color: Rgba) {
+        let name = format!("Color {}", self.swatches.len() + 1);
+        self.swatches.push(Swatch { name, color });
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        if index < self.swatches.len() {
+            self.swatches.remove(index);
+        }
+    }
+}
+
+// On-disk JSON shape shared by the exporter and importer
+#[derive(Serialize, Deserialize)]
+pub(crate) struct JsonPalette {
+    #[serde(default)]
+    pub(crate) name: String,
+    pub(crate) colors: Vec<JsonSwatch>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct JsonSwatch {
+    #[serde(default)]
+    pub(crate) name: String,
+    pub(crate) hex: String,
+}
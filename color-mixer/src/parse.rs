@@ -0,0 +1,248 @@
+use std::fmt;
+
+use gpui::{Hsla, Rgba, rgba};
+
+use crate::named_colors;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseColorError {
+    Empty,
+    InvalidHex(String),
+    UnknownName(String),
+    UnknownFunction(String),
+    ArgumentCount { function: String, found: usize },
+    InvalidArgument(String),
+}
+
+impl fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseColorError::Empty => write!(f, "enter a color"),
+            ParseColorError::InvalidHex(hex) => {
+                write!(f, "\"{hex}\" is not #rgb, #rrggbb or #rrggbbaa")
+            }
+            ParseColorError::UnknownName(name) => write!(f, "unknown color name \"{name}\""),
+            ParseColorError::UnknownFunction(name) => {
+                write!(f, "unsupported color function \"{name}()\"")
+            }
+            ParseColorError::ArgumentCount { function, found } => {
+                write!(f, "{function}() takes 3 or 4 arguments, found {found}")
+            }
+            ParseColorError::InvalidArgument(arg) => write!(f, "invalid argument \"{arg}\""),
+        }
+    }
+}
+
+impl std::error::Error for ParseColorError {}
+
+/// Parses hex notation, `rgb()`/`rgba()`, `hsl()`/`hsla()` or a CSS named color
+pub fn parse_color(input: &str) -> Result<Rgba, ParseColorError> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err(ParseColorError::Empty);
+    }
+
+    if let Some(hex) = input.strip_prefix('#') {
+        return parse_hex(hex);
+    }
+
+    if let Some((name, rest)) = input.split_once('(') {
+        let args = rest
+            .strip_suffix(')')
+            .ok_or_else(|| ParseColorError::InvalidArgument(rest.to_string()))?;
+        return parse_function(&name.trim().to_ascii_lowercase(), args);
+    }
+
+    if input.eq_ignore_ascii_case("transparent") {
+        return Ok(rgba(0x00000000));
+    }
+
+    named_colors::lookup(input)
+        .map(|hex| rgba(hex << 8 | 0xff))
+        .ok_or_else(|| ParseColorError::UnknownName(input.to_string()))
+}
+
+/// Parses hex notation only, `#` included, for formats that allow nothing else
+pub fn parse_hex_color(input: &str) -> Result<Rgba, ParseColorError> {
+    match input.strip_prefix('#') {
+        Some(hex) => parse_hex(hex),
+        None => Err(ParseColorError::InvalidHex(input.to_string())),
+    }
+}
+
+fn parse_hex(hex: &str) -> Result<Rgba, ParseColorError> {
+    let invalid = || ParseColorError::InvalidHex(format!("#{hex}"));
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(invalid());
+    }
+
+    // Expand the short forms by doubling every digit
+    let expanded = match hex.len() {
+        3 | 4 => hex.chars().flat_map(|c| [c, c]).collect(),
+        6 | 8 => hex.to_string(),
+        _ => return Err(invalid()),
+    };
+    let value = u32::from_str_radix(&expanded, 16).map_err(|_| invalid())?;
+
+    Ok(if expanded.len() == 6 {
+        rgba(value << 8 | 0xff)
+    } else {
+        rgba(value)
+    })
+}
+
+fn parse_function(name: &str, args: &str) -> Result<Rgba, ParseColorError> {
+    let is_hsl = match name {
+        "rgb" | "rgba" => false,
+        "hsl" | "hsla" => true,
+        _ => return Err(ParseColorError::UnknownFunction(name.to_string())),
+    };
+
+    // Accept both the legacy comma syntax and the modern `r g b / a` syntax
+    let args: Vec<&str> = if args.contains(',') {
+        args.split(',').map(str::trim).collect()
+    } else {
+        args.split(|c: char| c.is_whitespace() || c == '/')
+            .filter(|arg| !arg.is_empty())
+            .collect()
+    };
+    if !(3..=4).contains(&args.len()) {
+        return Err(ParseColorError::ArgumentCount {
+            function: name.to_string(),
+            found: args.len(),
+        });
+    }
+    let alpha = args
+        .get(3)
+        .map(|arg| parse_alpha(arg))
+        .transpose()?
+        .unwrap_or(1.0);
+
+    if is_hsl {
+        Ok(Hsla {
+            h: parse_hue(args[0])?,
+            s: parse_percentage(args[1])?,
+            l: parse_percentage(args[2])?,
+            a: alpha,
+        }
+        .to_rgb())
+    } else {
+        Ok(Rgba {
+            r: parse_rgb_component(args[0])?,
+            g: parse_rgb_component(args[1])?,
+            b: parse_rgb_component(args[2])?,
+            a: alpha,
+        })
+    }
+}
+
+fn parse_number(arg: &str) -> Result<f32, ParseColorError> {
+    arg.parse::<f32>()
+        .ok()
+        .filter(|value| value.is_finite())
+        .ok_or_else(|| ParseColorError::InvalidArgument(arg.to_string()))
+}
+
+// Integers are 0..255, percentages 0%..100%
+fn parse_rgb_component(arg: &str) -> Result<f32, ParseColorError> {
+    let value = match arg.strip_suffix('%') {
+        Some(percent) => parse_number(percent)? / 100.0,
+        None => parse_number(arg)? / 255.0,
+    };
+    Ok(value.clamp(0.0, 1.0))
+}
+
+// Percentages, or bare numbers on the same 0..100 scale as CSS Color 4 allows
+fn parse_percentage(arg: &str) -> Result<f32, ParseColorError> {
+    let value = parse_number(arg.strip_suffix('%').unwrap_or(arg))?;
+    Ok((value / 100.0).clamp(0.0, 1.0))
+}
+
+fn parse_alpha(arg: &str) -> Result<f32, ParseColorError> {
+    let value = match arg.strip_suffix('%') {
+        Some(percent) => parse_number(percent)? / 100.0,
+        None => parse_number(arg)?,
+    };
+    Ok(value.clamp(0.0, 1.0))
+}
+
+// Returns the hue as a 0..1 fraction of a turn
+fn parse_hue(arg: &str) -> Result<f32, ParseColorError> {
+    let degrees = if let Some(turns) = arg.strip_suffix("turn") {
+        parse_number(turns)? * 360.0
+    } else if let Some(radians) = arg.strip_suffix("rad") {
+        parse_number(radians)?.to_degrees()
+    } else {
+        parse_number(arg.strip_suffix("deg").unwrap_or(arg))?
+    };
+    Ok(degrees.rem_euclid(360.0) / 360.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::to_hex;
+
+    fn hex(input: &str) -> String {
+        to_hex(parse_color(input).unwrap())
+    }
+
+    #[test]
+    fn parses_hex_notations() {
+        assert_eq!(hex("#1976D2"), "#1976d2");
+        assert_eq!(hex("#fa0"), "#ffaa00");
+        assert_eq!(hex("#11223380"), "#11223380");
+        assert_eq!(hex("  #FFF8  "), "#ffffff88");
+
+        assert_eq!(parse_hex_color("#fa0"), parse_color("#fa0"));
+        assert!(matches!(
+            parse_hex_color("red"),
+            Err(ParseColorError::InvalidHex(_))
+        ));
+    }
+
+    #[test]
+    fn parses_css_functions() {
+        assert_eq!(hex("rgb(255, 128, 0)"), "#ff8000");
+        assert_eq!(hex("rgba(255 0 0 / 50%)"), "#ff000080");
+        assert_eq!(hex("rgb(100%, 0%, 0%)"), "#ff0000");
+        assert_eq!(hex("hsl(120, 100%, 50%)"), "#00ff00");
+        assert_eq!(hex("hsl(0.5turn 100% 50%)"), "#00ffff");
+    }
+
+    #[test]
+    fn parses_named_colors() {
+        assert_eq!(hex("rebeccapurple"), "#663399");
+        assert_eq!(hex("DodgerBlue"), "#1e90ff");
+        assert_eq!(hex("transparent"), "#00000000");
+    }
+
+    #[test]
+    fn reports_errors() {
+        assert_eq!(parse_color(""), Err(ParseColorError::Empty));
+        assert!(matches!(
+            parse_color("#12345"),
+            Err(ParseColorError::InvalidHex(_))
+        ));
+        assert!(matches!(
+            parse_color("#ggg"),
+            Err(ParseColorError::InvalidHex(_))
+        ));
+        assert!(matches!(
+            parse_color("blurple"),
+            Err(ParseColorError::UnknownName(_))
+        ));
+        assert!(matches!(
+            parse_color("lab(50 20 30)"),
+            Err(ParseColorError::UnknownFunction(_))
+        ));
+        assert!(matches!(
+            parse_color("rgb(1, 2)"),
+            Err(ParseColorError::ArgumentCount { found: 2, .. })
+        ));
+        assert!(matches!(
+            parse_color("rgb(1, x, 2)"),
+            Err(ParseColorError::InvalidArgument(_))
+        ));
+    }
+}
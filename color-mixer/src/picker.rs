@@ -0,0 +1,250 @@
+use std::rc::Rc;
+
+use gpui::{
+    App, Bounds, Corners, CursorStyle, DefiniteLength, DispatchPhase, ElementId, HitboxBehavior,
+    Hsla, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, Pixels, Point, Window, canvas,
+    div, fill, hsla, linear_color_stop, linear_gradient, point, prelude::*, px, relative, rgb,
+    size, transparent_black,
+};
+
+use gpui_color_mixer::color::Hsv;
+
+const MARKER_SIZE: Pixels = px(12.0);
+const HUE_STRIP_HEIGHT: Pixels = px(16.0);
+// Same as `rounded_md`
+const CORNER_RADIUS: Pixels = px(6.0);
+
+/// Reports the pointer position as 0..1 fractions of the surface's width and height
+pub type PointHandler = Rc<dyn Fn(f32, f32, &mut Window, &mut App)>;
+type HsvHandler = Rc<dyn Fn(&Hsv, &mut Window, &mut App)>;
+type HueHandler = Rc<dyn Fn(&f32, &mut Window, &mut App)>;
+/// Paints a picker's colors into the bounds of its surface
+pub type Painter = Box<dyn FnOnce(Bounds<Pixels>, &mut Window)>;
+
+/// Saturation (left to right) by value (bottom to top) square at a fixed hue,
+/// painted on a canvas as white and black gradients over the pure hue
+#[derive(IntoElement)]
+pub struct SaturationValueSquare {
+    id: ElementId,
+    hsv: Hsv,
+    on_change: Option<HsvHandler>,
+}
+
+impl SaturationValueSquare {
+    pub fn new(id: impl Into<ElementId>, hsv: Hsv) -> Self {
+        Self {
+            id: id.into(),
+            hsv,
+            on_change: None,
+        }
+    }
+
+    pub fn on_change(mut self, handler: impl Fn(&Hsv, &mut Window, &mut App) + 'static) -> Self {
+        self.on_change = Some(Rc::new(handler));
+        self
+    }
+}
+
+impl RenderOnce for SaturationValueSquare {
+    fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let hsv = self.hsv;
+        let hue = hsla(hsv.h, 1.0, 0.5, 1.0);
+        let on_change = self.on_change.map(|on_change| -> PointHandler {
+            Rc::new(move |x, y, window, cx| {
+                let hsv = Hsv {
+                    h: hsv.h,
+                    s: x,
+                    v: 1.0 - y,
+                };
+                on_change(&hsv, window, cx)
+            })
+        });
+
+        let paint: Painter = Box::new(move |bounds, window| {
+            window.paint_quad(fill(bounds, hue).corner_radii(CORNER_RADIUS));
+            window.paint_quad(
+                fill(
+                    bounds,
+                    linear_gradient(
+                        90.0,
+                        linear_color_stop(gpui::white(), 0.0),
+                        linear_color_stop(hsla(0.0, 0.0, 1.0, 0.0), 1.0),
+                    ),
+                )
+                .corner_radii(CORNER_RADIUS),
+            );
+            window.paint_quad(
+                fill(
+                    bounds,
+                    linear_gradient(
+                        180.0,
+                        linear_color_stop(transparent_black(), 0.0),
+                        linear_color_stop(gpui::black(), 1.0),
+                    ),
+                )
+                .corner_radii(CORNER_RADIUS),
+            );
+        });
+
+        div()
+            .relative()
+            .w_full()
+            .h_40()
+            .child(pointer_surface(self.id, Some(paint), on_change, window, cx))
+            .child(marker(
+                Hsla::from(hsv.to_rgb(1.0)),
+                relative(hsv.s),
+                relative(1.0 - hsv.v),
+            ))
+    }
+}
+
+/// Horizontal strip of every hue, left to right from red back to red
+#[derive(IntoElement)]
+pub struct HueStrip {
+    id: ElementId,
+    hue: f32,
+    on_change: Option<HueHandler>,
+}
+
+impl HueStrip {
+    pub fn new(id: impl Into<ElementId>, hue: f32) -> Self {
+        Self {
+            id: id.into(),
+            hue: hue.rem_euclid(1.0),
+            on_change: None,
+        }
+    }
+
+    pub fn on_change(mut self, handler: impl Fn(&f32, &mut Window, &mut App) + 'static) -> Self {
+        self.on_change = Some(Rc::new(handler));
+        self
+    }
+}
+
+impl RenderOnce for HueStrip {
+    fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
+        // Gradients only take two stops, so the wheel is painted as six
+        // segments between the primary and secondary colors
+        let paint: Painter = Box::new(|bounds, window| {
+            let width = bounds.size.width / 6.0;
+            for segment in 0..6 {
+                let from = hsla(segment as f32 / 6.0, 1.0, 0.5, 1.0);
+                let to = hsla((segment + 1) as f32 / 6.0 % 1.0, 1.0, 0.5, 1.0);
+                let origin = point(bounds.left() + width * segment as f32, bounds.top());
+                let mut corners = Corners::default();
+                if segment == 0 {
+                    corners.top_left = CORNER_RADIUS;
+                    corners.bottom_left = CORNER_RADIUS;
+                } else if segment == 5 {
+                    corners.top_right = CORNER_RADIUS;
+                    corners.bottom_right = CORNER_RADIUS;
+                }
+                window.paint_quad(
+                    fill(
+                        Bounds::new(origin, size(width, bounds.size.height)),
+                        linear_gradient(
+                            90.0,
+                            linear_color_stop(from, 0.0),
+                            linear_color_stop(to, 1.0),
+                        ),
+                    )
+                    .corner_radii(corners),
+                );
+            }
+        });
+        let on_change = self.on_change.map(|on_change| -> PointHandler {
+            Rc::new(move |x, _, window, cx| on_change(&x, window, cx))
+        });
+
+        div()
+            .relative()
+            .w_full()
+            .h(HUE_STRIP_HEIGHT)
+            .child(pointer_surface(self.id, Some(paint), on_change, window, cx))
+            .child(marker(
+                hsla(self.hue, 1.0, 0.5, 1.0),
+                relative(self.hue),
+                relative(0.5),
+            ))
+    }
+}
+
+// Ring centered on the given position, filled with the color it marks
+fn marker(color: Hsla, left: DefiniteLength, top: DefiniteLength) -> impl IntoElement {
+    div()
+        .absolute()
+        .left(left)
+        .top(top)
+        .ml(-MARKER_SIZE / 2.0)
+        .mt(-MARKER_SIZE / 2.0)
+        .size(MARKER_SIZE)
+        .rounded_full()
+        .border_2()
+        .border_color(rgb(0xffffff))
+        .shadow_sm()
+        .bg(color)
+}
+
+/// Canvas filling its parent that paints a picker's colors, if given, and
+/// reports presses and drags. Without a painter it is an invisible layer, as
+/// over an image. Like `Slider`, the drag continues when the pointer leaves
+/// the surface.
+pub fn pointer_surface(
+    id: ElementId,
+    paint: Option<Painter>,
+    on_change: Option<PointHandler>,
+    window: &mut Window,
+    cx: &mut App,
+) -> impl IntoElement + use<> {
+    let dragging = window.use_keyed_state(id, cx, |_, _| false);
+
+    canvas(
+        |bounds, window, _| window.insert_hitbox(bounds, HitboxBehavior::Normal),
+        move |bounds, hitbox, window, _| {
+            if let Some(paint) = paint {
+                paint(bounds, window);
+            }
+            window.set_cursor_style(CursorStyle::Crosshair, &hitbox);
+            let Some(on_change) = on_change else {
+                return;
+            };
+            let report = move |position: Point<Pixels>, window: &mut Window, cx: &mut App| {
+                let x = ((position.x - bounds.left()) / bounds.size.width).clamp(0.0, 1.0);
+                let y = ((position.y - bounds.top()) / bounds.size.height).clamp(0.0, 1.0);
+                on_change(x, y, window, cx);
+            };
+
+            window.on_mouse_event({
+                let dragging = dragging.clone();
+                let report = report.clone();
+                move |event: &MouseDownEvent, phase, window, cx| {
+                    if phase == DispatchPhase::Bubble
+                        && event.button == MouseButton::Left
+                        && hitbox.is_hovered(window)
+                    {
+                        dragging.update(cx, |dragging, _| *dragging = true);
+                        report(event.position, window, cx);
+                    }
+                }
+            });
+
+            window.on_mouse_event({
+                let dragging = dragging.clone();
+                move |event: &MouseMoveEvent, phase, window, cx| {
+                    if phase == DispatchPhase::Bubble && event.dragging() && *dragging.read(cx) {
+                        report(event.position, window, cx);
+                    }
+                }
+            });
+
+            window.on_mouse_event(move |_: &MouseUpEvent, _, _, cx| {
+                if *dragging.read(cx) {
+                    dragging.update(cx, |dragging, _| *dragging = false);
+                }
+            });
+        },
+    )
+    .absolute()
+    .size_full()
+}
@@ -0,0 +1,250 @@
+use std::fmt;
+use std::path::Path;
+
+use gpui::Rgba;
+use image::{ImageError, RgbaImage};
+
+// Quantization looks at no more than this many pixels, skipping evenly
+const MAX_SAMPLES: u32 = 65_536;
+// Pixels more transparent than this are ignored when building a palette
+const MIN_ALPHA: u8 = 128;
+
+#[derive(Debug)]
+pub enum ImageLoadError {
+    Decode(ImageError),
+    // Zero width or height, which leaves nothing to sample
+    Empty { width: u32, height: u32 },
+}
+
+impl fmt::Display for ImageLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ImageLoadError::Decode(error) => write!(f, "{error}"),
+            ImageLoadError::Empty { width, height } => {
+                write!(f, "the image is {width}×{height} and has no pixels")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ImageLoadError {}
+
+/// A decoded image kept in memory for sampling and palette extraction,
+/// always at least one pixel in size
+pub struct SampledImage {
+    pixels: RgbaImage,
+}
+
+impl SampledImage {
+    pub fn open(path: &Path) -> Result<Self, ImageLoadError> {
+        let image = image::open(path).map_err(ImageLoadError::Decode)?;
+        Self::new(image.to_rgba8())
+    }
+
+    pub fn new(pixels: RgbaImage) -> Result<Self, ImageLoadError> {
+        let (width, height) = pixels.dimensions();
+        if width == 0 || height == 0 {
+            return Err(ImageLoadError::Empty { width, height });
+        }
+        Ok(Self { pixels })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.pixels.width()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.pixels.height()
+    }
+
+    /// The color at a pixel, clamping coordinates to the image
+    pub fn pixel(&self, x: u32, y: u32) -> Rgba {
+        let x = x.min(self.width().saturating_sub(1));
+        let y = y.min(self.height().saturating_sub(1));
+        let [r, g, b, a] = self.pixels.get_pixel(x, y).0;
+        Rgba {
+            r: r as f32 / 255.0,
+            g: g as f32 / 255.0,
+            b: b as f32 / 255.0,
+            a: a as f32 / 255.0,
+        }
+    }
+
+    /// The color under a point given as 0..1 fractions of width and height
+    pub fn pixel_at(&self, x: f32, y: f32) -> Rgba {
+        self.pixel(
+            (x * self.width() as f32) as u32,
+            (y * self.height() as f32) as u32,
+        )
+    }
+
+    /// Up to `count` dominant opaque colors, most common first
+    pub fn palette(&self, count: usize) -> Vec<Rgba> {
+        let total = self.width() as u64 * self.height() as u64;
+        let stride = total.div_ceil(MAX_SAMPLES as u64).max(1) as usize;
+        let pixels = self
+            .pixels
+            .pixels()
+            .step_by(stride)
+            .filter(|pixel| pixel.0[3] >= MIN_ALPHA)
+            .map(|pixel| [pixel.0[0], pixel.0[1], pixel.0[2]])
+            .collect();
+        median_cut(pixels, count)
+    }
+}
+
+/// Median-cut quantization: repeatedly splits the box of colors with the
+/// widest channel range at its median until there are `count` boxes, then
+/// averages each box. Results are ordered by the number of pixels they cover.
+pub fn median_cut(pixels: Vec<[u8; 3]>, count: usize) -> Vec<Rgba> {
+    if pixels.is_empty() || count == 0 {
+        return Vec::new();
+    }
+
+    let mut boxes = vec![pixels];
+    while boxes.len() < count {
+        // Boxes of a single color have a range of zero and can't be split
+        let Some((index, channel, _)) = boxes
+            .iter()
+            .enumerate()
+            .map(|(index, pixels)| {
+                let (channel, range) = widest_channel(pixels);
+                (index, channel, range)
+            })
+            .filter(|(_, _, range)| *range > 0)
+            .max_by_key(|(_, _, range)| *range)
+        else {
+            break;
+        };
+
+        let mut pixels = boxes.swap_remove(index);
+        pixels.sort_unstable_by_key(|pixel| pixel[channel]);
+        // Split between distinct values so neither half is empty
+        let median = pixels[pixels.len() / 2][channel];
+        let split = match pixels.partition_point(|pixel| pixel[channel] < median) {
+            0 => pixels.partition_point(|pixel| pixel[channel] <= median),
+            split => split,
+        };
+        let upper = pixels.split_off(split);
+        boxes.push(pixels);
+        boxes.push(upper);
+    }
+
+    boxes.sort_by_key(|pixels| std::cmp::Reverse(pixels.len()));
+    boxes.iter().map(|pixels| average(pixels)).collect()
+}
+
+fn widest_channel(pixels: &[[u8; 3]]) -> (usize, u8) {
+    (0..3)
+        .map(|channel| {
+            let (min, max) = pixels.iter().fold((u8::MAX, u8::MIN), |(min, max), pixel| {
+                (min.min(pixel[channel]), max.max(pixel[channel]))
+            });
+            (channel, max - min)
+        })
+        .max_by_key(|(_, range)| *range)
+        .unwrap()
+}
+
+fn average(pixels: &[[u8; 3]]) -> Rgba {
+    let mut sum = [0u64; 3];
+    for pixel in pixels {
+        for (total, value) in sum.iter_mut().zip(pixel) {
+            *total += *value as u64;
+        }
+    }
+    let [r, g, b] = sum.map(|total| total as f32 / pixels.len() as f32 / 255.0);
+    Rgba { r, g, b, a: 1.0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::Rgba as Pixel;
+
+    use super::*;
+    use crate::color::to_hex;
+
+    fn fixture(name: &str) -> SampledImage {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(name);
+        SampledImage::open(&path).unwrap()
+    }
+
+    fn hexes(colors: &[Rgba]) -> Vec<String> {
+        colors.iter().copied().map(to_hex).collect()
+    }
+
+    #[test]
+    fn png_stripes_quantize_to_their_exact_colors() {
+        // 40, 30, 20 and 10 pixel wide stripes of red, green, blue and yellow
+        let image = fixture("stripes.png");
+        assert_eq!(
+            hexes(&image.palette(4)),
+            ["#ff0000", "#00ff00", "#0000ff", "#ffff00"]
+        );
+        // Asking for more colors than exist returns only the distinct ones
+        assert_eq!(image.palette(16).len(), 4);
+        assert_eq!(to_hex(image.pixel_at(0.0, 0.5)), "#ff0000");
+        assert_eq!(to_hex(image.pixel_at(0.95, 0.5)), "#ffff00");
+        assert_eq!(to_hex(image.pixel(1000, 1000)), "#ffff00");
+    }
+
+    #[test]
+    fn jpeg_halves_quantize_close_to_their_colors() {
+        // Left half #1976d2, right half #ffc107, with JPEG noise at the seam
+        let image = fixture("halves.jpg");
+        let palette = image.palette(2);
+        assert_eq!(palette.len(), 2);
+
+        let expected = [gpui::rgb(0x1976d2), gpui::rgb(0xffc107)];
+        for expected in expected {
+            let closest = palette
+                .iter()
+                .map(|color| {
+                    (color.r - expected.r).abs()
+                        + (color.g - expected.g).abs()
+                        + (color.b - expected.b).abs()
+                })
+                .fold(f32::MAX, f32::min);
+            assert!(
+                closest < 0.06,
+                "{} in {:?}",
+                to_hex(expected),
+                hexes(&palette)
+            );
+        }
+    }
+
+    #[test]
+    fn transparent_pixels_are_ignored() {
+        let mut pixels = RgbaImage::from_pixel(4, 4, Pixel([0, 0, 0, 0]));
+        pixels.put_pixel(0, 0, Pixel([10, 20, 30, 255]));
+        let image = SampledImage::new(pixels).unwrap();
+        assert_eq!(hexes(&image.palette(5)), ["#0a141e"]);
+    }
+
+    #[test]
+    fn empty_images_are_rejected() {
+        for (width, height) in [(0, 0), (0, 3), (3, 0)] {
+            let error = SampledImage::new(RgbaImage::new(width, height))
+                .err()
+                .unwrap();
+            assert!(matches!(error, ImageLoadError::Empty { .. }));
+            assert_eq!(
+                error.to_string(),
+                format!("the image is {width}×{height} and has no pixels")
+            );
+        }
+    }
+
+    #[test]
+    fn median_cut_handles_degenerate_input() {
+        assert!(median_cut(Vec::new(), 5).is_empty());
+        assert!(median_cut(vec![[1, 2, 3]], 0).is_empty());
+        assert_eq!(
+            hexes(&median_cut(vec![[255, 255, 255]; 10], 5)),
+            ["#ffffff"]
+        );
+    }
+}
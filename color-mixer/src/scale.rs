@@ -0,0 +1,203 @@
+use std::fmt::Write;
+
+use gpui::Rgba;
+
+use crate::color::{Oklch, to_hex};
+use crate::palette::{Palette, Swatch};
+
+/// Tailwind-style step numbers; the base color sits at 500
+pub const STEPS: [u16; 11] = [50, 100, 200, 300, 400, 500, 600, 700, 800, 900, 950];
+const BASE_STEP: u16 = 500;
+
+// OKLCH lightness of the 50 and 950 ends, unless the base is already beyond them
+const LIGHTEST: f32 = 0.97;
+const DARKEST: f32 = 0.26;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScaleStep {
+    pub step: u16,
+    pub color: Rgba,
+}
+
+/// Tints and shades of one base color, lightest first
+#[derive(Clone, Debug, PartialEq)]
+pub struct Scale {
+    pub steps: Vec<ScaleStep>,
+}
+
+impl Scale {
+    /// Spreads OKLCH lightness from the base out to near-white and near-black
+    /// at constant hue and chroma, mapping each step into the sRGB gamut.
+    /// `curve` is an exponent on the distance from 500: 1.0 spaces steps
+    /// evenly, higher values keep 400/600 closer to the base, lower values
+    /// push them towards the ends.
+    pub fn new(base: Rgba, curve: f32) -> Self {
+        let base = Rgba { a: 1.0, ..base };
+        let lch = Oklch::from(base);
+        let lightest = LIGHTEST.max(lch.l);
+        let darkest = DARKEST.min(lch.l);
+
+        let steps = STEPS
+            .iter()
+            .map(|&step| {
+                let color = if step == BASE_STEP {
+                    base
+                } else {
+                    // 1.0 at 50 and 950, falling to 0.0 at 500
+                    let distance = (step.abs_diff(BASE_STEP) as f32 / 450.0).powf(curve);
+                    let l = if step < BASE_STEP {
+                        lch.l + (lightest - lch.l) * distance
+                    } else {
+                        lch.l - (lch.l - darkest) * distance
+                    };
+                    Oklch { l, ..lch }.to_rgb_clamped(1.0)
+                };
+                ScaleStep { step, color }
+            })
+            .collect();
+        Self { steps }
+    }
+
+    /// CSS custom properties on `:root`, e.g. `--brand-50`
+    pub fn to_css(&self, name: &str) -> String {
+        let name = token_name(name);
+        let mut css = String::from(":root {\n");
+        for step in &self.steps {
+            writeln!(css, "  --{name}-{}: {};", step.step, to_hex(step.color)).unwrap();
+        }
+        css.push_str("}\n");
+        css
+    }
+
+    /// A group in the W3C Design Tokens format, keyed by step
+    pub fn to_tokens(&self, name: &str) -> String {
+        // Written by hand to keep the steps in order; `token_name` and hex
+        // strings never need escaping
+        let mut json = format!("{{\n  \"{}\": {{\n", token_name(name));
+        for (index, step) in self.steps.iter().enumerate() {
+            let separator = if index + 1 < self.steps.len() {
+                ","
+            } else {
+                ""
+            };
+            writeln!(
+                json,
+                "    \"{}\": {{ \"$type\": \"color\", \"$value\": \"{}\" }}{separator}",
+                step.step,
+                to_hex(step.color)
+            )
+            .unwrap();
+        }
+        json.push_str("  }\n}\n");
+        json
+    }
+
+    /// Swatches named like the tokens, e.g. "brand-500"
+    pub fn to_palette(&self, name: &str) -> Palette {
+        let name = token_name(name);
+        let mut palette = Palette::new(name.clone());
+        for step in &self.steps {
+            palette.swatches.push(Swatch {
+                name: format!("{name}-{}", step.step),
+                color: step.color,
+            });
+        }
+        palette
+    }
+}
+
+// Lowercase words joined by hyphens, e.g. "Brand Blue" -> "brand-blue"
+fn token_name(name: &str) -> String {
+    let name = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+        .to_ascii_lowercase();
+    if name.is_empty() {
+        "color".to_string()
+    } else {
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::{rgb, rgba};
+    use serde_json::{Value, json};
+
+    use super::*;
+
+    #[test]
+    fn lightness_falls_from_50_to_950_through_the_base() {
+        let base = rgb(0x1976d2);
+        let scale = Scale::new(base, 1.0);
+        assert_eq!(
+            scale.steps.iter().map(|step| step.step).collect::<Vec<_>>(),
+            STEPS
+        );
+        assert_eq!(scale.steps[5].color, base);
+
+        let lightness: Vec<f32> = scale
+            .steps
+            .iter()
+            .map(|step| Oklch::from(step.color).l)
+            .collect();
+        assert!(
+            lightness.windows(2).all(|pair| pair[0] > pair[1]),
+            "{lightness:?}"
+        );
+        assert!((lightness[0] - LIGHTEST).abs() < 0.01);
+        assert!((lightness[10] - DARKEST).abs() < 0.01);
+
+        // Mid steps keep the hue; the ends lose chroma to the gamut instead
+        let hue = Oklch::from(base).h;
+        for step in &scale.steps[2..9] {
+            assert!((Oklch::from(step.color).h - hue).abs() < 2.0);
+        }
+    }
+
+    #[test]
+    fn curve_controls_how_fast_steps_leave_the_base() {
+        let base = rgb(0x4caf50);
+        let l = |curve: f32| Oklch::from(Scale::new(base, curve).steps[4].color).l;
+        let base_l = Oklch::from(base).l;
+        assert!(l(2.0) - base_l < l(1.0) - base_l);
+        assert!(l(1.0) - base_l < l(0.5) - base_l);
+    }
+
+    #[test]
+    fn extreme_bases_stay_in_order() {
+        let scale = Scale::new(rgba(0xfefefe80), 1.0);
+        assert_eq!(to_hex(scale.steps[0].color), "#fefefe");
+        assert_eq!(scale.steps[5].color.a, 1.0);
+        assert!(
+            scale
+                .steps
+                .windows(2)
+                .all(|pair| Oklch::from(pair[0].color).l >= Oklch::from(pair[1].color).l)
+        );
+    }
+
+    #[test]
+    fn exports_named_token_groups() {
+        let scale = Scale::new(rgb(0x1976d2), 1.0);
+        let css = scale.to_css("Brand Blue");
+        assert!(css.starts_with(":root {\n  --brand-blue-50: #"));
+        assert!(css.contains("  --brand-blue-500: #1976d2;\n"));
+        assert_eq!(css.lines().count(), STEPS.len() + 2);
+
+        let text = scale.to_tokens("Brand Blue");
+        assert!(text.starts_with("{\n  \"brand-blue\": {\n    \"50\": "));
+        let tokens: Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(
+            tokens["brand-blue"]["500"],
+            json!({ "$type": "color", "$value": "#1976d2" })
+        );
+        assert_eq!(tokens["brand-blue"].as_object().unwrap().len(), STEPS.len());
+
+        let palette = scale.to_palette("");
+        assert_eq!(palette.name, "color");
+        assert_eq!(palette.swatches[0].name, "color-50");
+    }
+}
@@ -0,0 +1,185 @@
+use std::rc::Rc;
+
+use gpui::{
+    App, CursorStyle, DispatchPhase, ElementId, HitboxBehavior, MouseButton, MouseDownEvent,
+    MouseMoveEvent, MouseUpEvent, Pixels, Window, canvas, div, prelude::*, px, relative, rgb,
+};
+
+// One 8-bit step, used while shift-dragging
+const FINE_STEP: f32 = 1.0 / 255.0;
+const PIXELS_PER_FINE_STEP: Pixels = px(2.0);
+const THUMB_WIDTH: Pixels = px(8.0);
+
+type ChangeHandler = Rc<dyn Fn(&f32, &mut Window, &mut App)>;
+
+#[derive(Clone, Copy)]
+struct Drag {
+    // Pointer position and value where the current drag segment started
+    anchor_x: Pixels,
+    anchor_value: f32,
+    fine: bool,
+}
+
+/// A horizontal 0..1 slider with a draggable thumb.
+///
+/// Clicking or dragging jumps to the pointer; holding shift switches to
+/// relative movement in `FINE_STEP` increments.
+#[derive(IntoElement)]
+pub struct Slider {
+    id: ElementId,
+    value: f32,
+    on_change: Option<ChangeHandler>,
+}
+
+impl Slider {
+    pub fn new(id: impl Into<ElementId>, value: f32) -> Self {
+        Self {
+            id: id.into(),
+            value: value.clamp(0.0, 1.0),
+            on_change: None,
+        }
+    }
+
+    pub fn on_change(mut self, handler: impl Fn(&f32, &mut Window, &mut App) + 'static) -> Self {
+        self.on_change = Some(Rc::new(handler));
+        self
+    }
+}
+
+impl RenderOnce for Slider {
+    fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let drag = window.use_keyed_state(self.id.clone(), cx, |_, _| None::<Drag>);
+        let dragging = drag.read(cx).is_some();
+        let value = self.value;
+        let on_change = self.on_change;
+
+        div()
+            .id(self.id)
+            .relative()
+            .flex()
+            .items_center()
+            .w_full()
+            .h_6()
+            // Track with the filled portion up to the current value
+            .child(
+                div()
+                    .w_full()
+                    .h_1p5()
+                    .rounded_full()
+                    .bg(rgb(0x505050))
+                    .child(
+                        div()
+                            .h_full()
+                            .w(relative(value))
+                            .rounded_full()
+                            .bg(rgb(0xd0d0d0)),
+                    ),
+            )
+            .child(
+                div()
+                    .absolute()
+                    .top_0()
+                    .bottom_0()
+                    .left(relative(value))
+                    .ml(-THUMB_WIDTH / 2.0)
+                    .w(THUMB_WIDTH)
+                    .rounded_sm()
+                    .border_1()
+                    .border_color(rgb(0x202020))
+                    .bg(if dragging {
+                        rgb(0xffffff)
+                    } else {
+                        rgb(0xe0e0e0)
+                    }),
+            )
+            .child(
+                canvas(
+                    |bounds, window, _| window.insert_hitbox(bounds, HitboxBehavior::Normal),
+                    move |bounds, hitbox, window, _| {
+                        window.set_cursor_style(CursorStyle::PointingHand, &hitbox);
+                        let Some(on_change) = on_change else {
+                            return;
+                        };
+                        let value_at = move |x: Pixels| {
+                            ((x - bounds.left()) / bounds.size.width).clamp(0.0, 1.0)
+                        };
+
+                        window.on_mouse_event({
+                            let drag = drag.clone();
+                            let on_change = on_change.clone();
+                            move |event: &MouseDownEvent, phase, window, cx| {
+                                if phase != DispatchPhase::Bubble
+                                    || event.button != MouseButton::Left
+                                    || !hitbox.is_hovered(window)
+                                {
+                                    return;
+                                }
+
+                                let fine = event.modifiers.shift;
+                                let new_value = if fine {
+                                    value
+                                } else {
+                                    value_at(event.position.x)
+                                };
+                                drag.update(cx, |drag, cx| {
+                                    *drag = Some(Drag {
+                                        anchor_x: event.position.x,
+                                        anchor_value: new_value,
+                                        fine,
+                                    });
+                                    cx.notify();
+                                });
+                                on_change(&new_value, window, cx);
+                            }
+                        });
+
+                        window.on_mouse_event({
+                            let drag = drag.clone();
+                            move |event: &MouseMoveEvent, phase, window, cx| {
+                                if phase != DispatchPhase::Bubble || !event.dragging() {
+                                    return;
+                                }
+                                let Some(mut state) = *drag.read(cx) else {
+                                    return;
+                                };
+
+                                // Re-anchor when shift is pressed or released mid-drag
+                                let fine = event.modifiers.shift;
+                                if fine != state.fine {
+                                    state = Drag {
+                                        anchor_x: event.position.x,
+                                        anchor_value: value,
+                                        fine,
+                                    };
+                                    drag.update(cx, |drag, _| *drag = Some(state));
+                                }
+
+                                let new_value = if fine {
+                                    let steps = ((event.position.x - state.anchor_x)
+                                        / PIXELS_PER_FINE_STEP)
+                                        .round();
+                                    (state.anchor_value + steps * FINE_STEP).clamp(0.0, 1.0)
+                                } else {
+                                    value_at(event.position.x)
+                                };
+                                if new_value != value {
+                                    on_change(&new_value, window, cx);
+                                }
+                            }
+                        });
+
+                        window.on_mouse_event(move |_: &MouseUpEvent, _, _, cx| {
+                            if drag.read(cx).is_some() {
+                                drag.update(cx, |drag, cx| {
+                                    *drag = None;
+                                    cx.notify();
+                                });
+                            }
+                        });
+                    },
+                )
+                .absolute()
+                .size_full(),
+            )
+    }
+}
@@ -0,0 +1,227 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use gpui::Rgba;
+use serde::{Deserialize, Serialize};
+
+use crate::color::{ColorMode, to_hex};
+use crate::palette::{JsonPalette, JsonSwatch, Palette, Swatch};
+use crate::parse::parse_color;
+
+/// Version written to new state files. Bump it, and migrate in `from_json`,
+/// whenever the shape below changes.
+pub const VERSION: u32 = 1;
+
+/// What the mixer restores on startup
+#[derive(Clone, Debug, PartialEq)]
+pub struct MixerState {
+    pub color: Rgba,
+    pub mode: ColorMode,
+    pub palette: Palette,
+    // Undo steps, oldest first
+    pub history: Vec<Rgba>,
+}
+
+#[derive(Debug)]
+pub enum StateError {
+    Io(String),
+    Corrupt(String),
+    // Most likely written by a newer build
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for StateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StateError::Io(message) => write!(f, "{message}"),
+            StateError::Corrupt(message) => write!(f, "corrupt state file: {message}"),
+            StateError::UnsupportedVersion(version) => {
+                write!(f, "unsupported state file version {version}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for StateError {}
+
+#[derive(Deserialize)]
+struct Header {
+    version: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StateV1 {
+    version: u32,
+    color: String,
+    mode: ColorMode,
+    palette: JsonPalette,
+    history: Vec<String>,
+}
+
+/// `$XDG_DATA_HOME/color-mixer/state.json` on Linux, and the platform's
+/// equivalent elsewhere
+pub fn default_path() -> Option<PathBuf> {
+    Some(dirs::data_dir()?.join("color-mixer").join("state.json"))
+}
+
+/// Reads the state file; `Ok(None)` means there is none yet
+pub fn load(path: &Path) -> Result<Option<MixerState>, StateError> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => from_json(&text).map(Some),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(StateError::Io(error.to_string())),
+    }
+}
+
+/// Writes through a temporary file so a crash never leaves half a file behind
+pub fn save(path: &Path, state: &MixerState) -> Result<(), StateError> {
+    let io = |error: std::io::Error| StateError::Io(error.to_string());
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(io)?;
+    }
+    let temporary = path.with_extension("json.tmp");
+    std::fs::write(&temporary, to_json(state)).map_err(io)?;
+    std::fs::rename(&temporary, path).map_err(io)
+}
+
+/// Moves an unreadable state file aside so the next save doesn't destroy it,
+/// returning where it went
+pub fn set_aside(path: &Path) -> Option<PathBuf> {
+    let backup = path.with_extension("json.bak");
+    std::fs::rename(path, &backup).ok()?;
+    Some(backup)
+}
+
+pub fn to_json(state: &MixerState) -> String {
+    let json = StateV1 {
+        version: VERSION,
+        color: to_hex(state.color),
+        mode: state.mode,
+        palette: JsonPalette {
+            name: state.palette.name.clone(),
+            colors: state
+                .palette
+                .swatches
+                .iter()
+                .map(|swatch| JsonSwatch {
+                    name: swatch.name.clone(),
+                    hex: to_hex(swatch.color),
+                })
+                .collect(),
+        },
+        history: state.history.iter().copied().map(to_hex).collect(),
+    };
+    let mut text = serde_json::to_string_pretty(&json).expect("state is always serializable");
+    text.push('\n');
+    text
+}
+
+pub fn from_json(text: &str) -> Result<MixerState, StateError> {
+    let corrupt = |error: serde_json::Error| StateError::Corrupt(error.to_string());
+    let header: Header = serde_json::from_str(text).map_err(corrupt)?;
+    if header.version != VERSION {
+        return Err(StateError::UnsupportedVersion(header.version));
+    }
+
+    let json: StateV1 = serde_json::from_str(text).map_err(corrupt)?;
+    let color = |hex: &str| {
+        parse_color(hex).map_err(|error| StateError::Corrupt(format!("`{hex}`: {error}")))
+    };
+    Ok(MixerState {
+        color: color(&json.color)?,
+        mode: json.mode,
+        palette: Palette {
+            name: json.palette.name,
+            swatches: json
+                .palette
+                .colors
+                .into_iter()
+                .map(|swatch| {
+                    Ok(Swatch {
+                        color: color(&swatch.hex)?,
+                        name: swatch.name,
+                    })
+                })
+                .collect::<Result<_, _>>()?,
+        },
+        history: json
+            .history
+            .iter()
+            .map(|hex| color(hex))
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::{rgb, rgba};
+
+    use super::*;
+
+    fn sample() -> MixerState {
+        let mut palette = Palette::new("Brand");
+        palette.add(rgb(0x1976d2));
+        palette.add(rgba(0xffc10780));
+        MixerState {
+            color: rgba(0x4caf50cc),
+            mode: ColorMode::Oklch,
+            palette,
+            history: vec![rgb(0x000000), rgb(0x808080)],
+        }
+    }
+
+    fn temporary_path(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("color-mixer-state-{}-{name}", std::process::id()))
+            .join("state.json")
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let state = sample();
+        let text = to_json(&state);
+        assert!(text.contains("\"version\": 1"));
+        assert!(text.contains("\"mode\": \"oklch\""));
+        assert_eq!(from_json(&text).unwrap(), state);
+    }
+
+    #[test]
+    fn saves_and_loads_files() {
+        let path = temporary_path("round-trip");
+        assert!(load(&path).unwrap().is_none());
+        save(&path, &sample()).unwrap();
+        assert_eq!(load(&path).unwrap(), Some(sample()));
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn rejects_corrupt_and_newer_files() {
+        assert!(matches!(
+            from_json("{ not json"),
+            Err(StateError::Corrupt(_))
+        ));
+        assert!(matches!(
+            from_json(r##"{"version": 1, "color": "#zzz"}"##),
+            Err(StateError::Corrupt(_))
+        ));
+        let bad_color = to_json(&sample()).replace("#4caf50cc", "#nope");
+        assert!(matches!(from_json(&bad_color), Err(StateError::Corrupt(_))));
+        assert!(matches!(
+            from_json(r#"{"version": 2, "something": "else"}"#),
+            Err(StateError::UnsupportedVersion(2))
+        ));
+    }
+
+    #[test]
+    fn corrupt_files_are_set_aside() {
+        let path = temporary_path("corrupt");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "garbage").unwrap();
+        assert!(matches!(load(&path), Err(StateError::Corrupt(_))));
+
+        let backup = set_aside(&path).unwrap();
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), "garbage");
+        assert!(load(&path).unwrap().is_none());
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,38 @@
+/// The light and dark `Theme` tokens from themed-person-list, so colors can be
+/// matched against the app theme as well as the loaded palette
+pub const THEME_COLORS: &[(&str, u32)] = &[
+    ("light.surface_primary", 0xFFFFFF),
+    ("light.surface_secondary", 0xF5F5F5),
+    ("light.surface_tertiary", 0xEEEEEE),
+    ("light.surface_elevated", 0xFFFFFF),
+    ("light.text_primary", 0x1A1A1A),
+    ("light.text_secondary", 0x666666),
+    ("light.text_disabled", 0xAAAAAA),
+    ("light.text_on_accent", 0xFFFFFF),
+    ("light.accent_primary", 0x1976D2),
+    ("light.accent_secondary", 0x388E3C),
+    ("light.accent_hover", 0x1565C0),
+    ("light.success", 0x4CAF50),
+    ("light.warning", 0xFF9800),
+    ("light.error", 0xF44336),
+    ("light.info", 0x2196F3),
+    ("light.border_subtle", 0xE0E0E0),
+    ("light.border_strong", 0xBDBDBD),
+    ("dark.surface_primary", 0x121212),
+    ("dark.surface_secondary", 0x1E1E1E),
+    ("dark.surface_tertiary", 0x2A2A2A),
+    ("dark.surface_elevated", 0x2C2C2C),
+    ("dark.text_primary", 0xFFFFFF),
+    ("dark.text_secondary", 0xB3B3B3),
+    ("dark.text_disabled", 0x666666),
+    ("dark.text_on_accent", 0xFFFFFF),
+    ("dark.accent_primary", 0x90CAF9),
+    ("dark.accent_secondary", 0xA5D6A7),
+    ("dark.accent_hover", 0xBBDEFB),
+    ("dark.success", 0x81C784),
+    ("dark.warning", 0xFFB74D),
+    ("dark.error", 0xE57373),
+    ("dark.info", 0x64B5F6),
+    ("dark.border_subtle", 0x383838),
+    ("dark.border_strong", 0x4F4F4F),
+];
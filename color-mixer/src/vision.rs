@@ -0,0 +1,129 @@
+use gpui::Rgba;
+
+use crate::color::{clamp_rgb, linear_to_srgb, srgb_to_linear};
+
+/// Color-vision deficiencies that can be simulated on rendered colors
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Deficiency {
+    Protanopia,
+    Deuteranopia,
+    Tritanopia,
+    Achromatopsia,
+}
+
+impl Deficiency {
+    pub const ALL: [Deficiency; 4] = [
+        Deficiency::Protanopia,
+        Deficiency::Deuteranopia,
+        Deficiency::Tritanopia,
+        Deficiency::Achromatopsia,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Deficiency::Protanopia => "Protan",
+            Deficiency::Deuteranopia => "Deutan",
+            Deficiency::Tritanopia => "Tritan",
+            Deficiency::Achromatopsia => "Achroma",
+        }
+    }
+
+    // Machado, Oliveira & Fernandes (2009) at full severity, in linear RGB.
+    // Every row sums to 1 so neutral grays are left unchanged.
+    fn matrix(self) -> [[f32; 3]; 3] {
+        match self {
+            Deficiency::Protanopia => [
+                [0.152286, 1.052583, -0.204868],
+                [0.114503, 0.786281, 0.099216],
+                [-0.003882, -0.048116, 1.051998],
+            ],
+            Deficiency::Deuteranopia => [
+                [0.367322, 0.860646, -0.227968],
+                [0.280085, 0.672501, 0.047413],
+                [-0.011820, 0.042940, 0.968881],
+            ],
+            Deficiency::Tritanopia => [
+                [1.255528, -0.076749, -0.178779],
+                [-0.078411, 0.930809, 0.147602],
+                [0.004733, 0.691367, 0.303900],
+            ],
+            // Total color blindness sees only luminance
+            Deficiency::Achromatopsia => [[0.2126, 0.7152, 0.0722]; 3],
+        }
+    }
+
+    /// Approximates how `color` appears to someone with this deficiency
+    pub fn simulate(self, color: Rgba) -> Rgba {
+        let linear = [color.r, color.g, color.b].map(srgb_to_linear);
+        let [r, g, b] = self
+            .matrix()
+            .map(|row| linear_to_srgb(row.iter().zip(linear).map(|(m, c)| m * c).sum()));
+        clamp_rgb(Rgba {
+            r,
+            g,
+            b,
+            a: color.a,
+        })
+    }
+}
+
+/// Applies an optional simulation, so callers can pass the current setting straight through
+pub fn simulate(deficiency: Option<Deficiency>, color: Rgba) -> Rgba {
+    deficiency.map_or(color, |deficiency| deficiency.simulate(color))
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::{rgb, rgba};
+
+    use super::*;
+    use crate::color::{Oklab, to_hex};
+
+    fn distance(a: Rgba, b: Rgba) -> f32 {
+        let (a, b) = (Oklab::from(a), Oklab::from(b));
+        ((a.l - b.l).powi(2) + (a.a - b.a).powi(2) + (a.b - b.b).powi(2)).sqrt()
+    }
+
+    #[test]
+    fn grays_and_alpha_are_preserved() {
+        for deficiency in Deficiency::ALL {
+            for gray in [0x000000, 0x808080, 0xffffff] {
+                assert_eq!(
+                    to_hex(deficiency.simulate(rgb(gray))),
+                    to_hex(rgb(gray)),
+                    "{deficiency:?}"
+                );
+            }
+            assert_eq!(deficiency.simulate(rgba(0xff000080)).a, 128.0 / 255.0);
+        }
+    }
+
+    #[test]
+    fn achromatopsia_removes_all_chroma() {
+        let simulated = Deficiency::Achromatopsia.simulate(rgb(0x1976d2));
+        assert_eq!(simulated.r, simulated.g);
+        assert_eq!(simulated.g, simulated.b);
+    }
+
+    #[test]
+    fn red_green_confusion_matches_the_deficiency() {
+        let (red, green) = (rgb(0xd32f2f), rgb(0x388e3c));
+        let normal = distance(red, green);
+        for deficiency in [Deficiency::Protanopia, Deficiency::Deuteranopia] {
+            let simulated = distance(deficiency.simulate(red), deficiency.simulate(green));
+            assert!(simulated < normal / 2.0, "{deficiency:?}: {simulated}");
+        }
+        // Tritanopes confuse blue and yellow, not red and green
+        let tritan = distance(
+            Deficiency::Tritanopia.simulate(red),
+            Deficiency::Tritanopia.simulate(green),
+        );
+        assert!(tritan > normal / 2.0, "{tritan}");
+    }
+
+    #[test]
+    fn no_deficiency_is_the_identity() {
+        let color = rgb(0x1976d2);
+        assert_eq!(simulate(None, color), color);
+    }
+}
@@ -0,0 +1,19 @@
+// Generated by color-mixer from the "Brand" palette
+
+use gpui::{Rgba, rgb, rgba};
+
+pub const PRIMARY: u32 = 0x1976D2; // Primary
+pub const ACCENT_HOVER: u32 = 0x1565C0; // Accent Hover
+pub const OVERLAY: u32 = 0x00000080; // Overlay
+
+pub fn primary() -> Rgba {
+    rgb(0x1976D2) // Primary
+}
+
+pub fn accent_hover() -> Rgba {
+    rgb(0x1565C0) // Accent Hover
+}
+
+pub fn overlay() -> Rgba {
+    rgba(0x00000080) // Overlay
+}
@@ -0,0 +1,24 @@
+// Generated by color-mixer from the "Keywords" palette
+
+use gpui::{Rgba, rgb, rgba};
+
+pub const TYPE: u32 = 0x000000; // type
+pub const MATCH: u32 = 0x808080; // Match
+pub const SELF: u32 = 0xFFFFFF; // self
+pub const COLOR_3_TONE: u32 = 0xFF9800; // 3 tone
+
+pub fn type_() -> Rgba {
+    rgb(0x000000) // type
+}
+
+pub fn match_() -> Rgba {
+    rgb(0x808080) // Match
+}
+
+pub fn self_() -> Rgba {
+    rgb(0xFFFFFF) // self
+}
+
+pub fn color_3_tone() -> Rgba {
+    rgb(0xFF9800) // 3 tone
+}
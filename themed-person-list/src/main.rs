@@ -1,22 +1,71 @@
-use gpui::{actions, App, Application, Bounds, WindowBounds, WindowOptions, prelude::*, px, size};
+use gpui::{actions, App, Application, Bounds, Focusable, WindowBounds, WindowOptions, prelude::*, px, size};
 
 mod person_list;
 mod person_list_item;
 
 mod theme;
+mod theme_picker;
 
 use person_list::PersonList;
 use person_list_item::PersonListItem;
 
-use crate::theme::Theme;
+use crate::theme::registry;
+use crate::theme::settings::{self, ThemeSettings};
+use crate::theme_picker::ToggleTheme;
 
 // Define a quit action
 actions!(app, [Quit]);
 
 fn main() {
     Application::new().run(|cx: &mut App| {
-        // Set theme globally so all components can access it
-        cx.set_global(Theme::dark());
+        // Usage: themed-person-list [--theme NAME] [THEME_FILE]
+        let mut theme_name = None;
+        let mut theme_file = None;
+        let mut args = std::env::args_os().skip(1);
+        while let Some(arg) = args.next() {
+            if arg == "--theme" {
+                theme_name = args.next();
+            } else {
+                theme_file = Some(arg);
+            }
+        }
+
+        // Set theme globally so all components can access it. By default it
+        // follows the system's light or dark appearance.
+        registry::set_appearance(cx.window_appearance(), cx);
+        // The theme last picked in the app, which a theme file or `--theme`
+        // shows over without replacing
+        let settings_path = settings::default_path();
+        let saved = settings_path.as_deref().and_then(settings::load);
+        if let Some(path) = settings_path {
+            cx.set_global(ThemeSettings { path });
+        }
+        // A theme file leaves out whatever its base theme provides, and
+        // saving it reloads the theme
+        let has_theme_file = theme_file.is_some();
+        if let Some(path) = theme_file {
+            theme::watcher::watch(path.into(), cx);
+        }
+        // `--theme` with any registered name, like "dark-high-contrast",
+        // overrides the system appearance
+        if let Some(name) = theme_name {
+            let name = name.to_string_lossy();
+            if !registry::select(&name, cx) {
+                eprintln!("unknown theme \"{name}\"");
+            }
+        } else if let Some(name) = saved.filter(|_| !has_theme_file) {
+            registry::select(&name, cx);
+        }
+
+        // `secondary` is cmd on macOS and ctrl elsewhere: secondary-shift-t
+        // flips light/dark, secondary-k secondary-t opens the theme picker and
+        // secondary-shift-v shows the color-vision preview
+        theme_picker::bind_keys(cx);
+        person_list::bind_keys(cx);
+        cx.on_action(|_: &ToggleTheme, cx| {
+            registry::toggle(cx);
+            settings::remember(cx);
+        });
         
         // Bind Cmd+Q to quit action
         cx.bind_keys([gpui::KeyBinding::new("cmd-q", Quit, None)]);
@@ -32,28 +81,31 @@ fn main() {
                 window_bounds: Some(WindowBounds::Windowed(bounds)),
                 ..Default::default()
             },
-            |_, cx| {
+            |window, cx| {
                 // Create PersonList component containing multiple PersonListItems
-                cx.new(|cx| {
+                let list = cx.new(|cx| {
                     let items = vec![
-                        cx.new(|_| PersonListItem::new("Mick", "Jagger")),
-                        cx.new(|_| PersonListItem::new("Curt", "Cobain")),
-                        cx.new(|_| PersonListItem::new("Paul", "McCartney")),
-                        cx.new(|_| PersonListItem::new("John", "Lennon")),
-                        cx.new(|_| PersonListItem::new("George", "Harrison")),
-                        cx.new(|_| PersonListItem::new("Ringo", "Starr")),
-                        cx.new(|_| PersonListItem::new("David", "Bowie")),
-                        cx.new(|_| PersonListItem::new("Freddie", "Mercury")),
-                        cx.new(|_| PersonListItem::new("Elvis", "Presley")),
-                        cx.new(|_| PersonListItem::new("Bob", "Dylan")),
-                        cx.new(|_| PersonListItem::new("Jimi", "Hendrix")),
-                        cx.new(|_| PersonListItem::new("Janis", "Joplin")),
-                        cx.new(|_| PersonListItem::new("Jim", "Morrison")),
-                        cx.new(|_| PersonListItem::new("Amy", "Winehouse")),
-                        cx.new(|_| PersonListItem::new("Whitney", "Houston")),
+                        cx.new(|cx| PersonListItem::new("Mick", "Jagger", cx)),
+                        cx.new(|cx| PersonListItem::new("Curt", "Cobain", cx)),
+                        cx.new(|cx| PersonListItem::new("Paul", "McCartney", cx)),
+                        cx.new(|cx| PersonListItem::new("John", "Lennon", cx)),
+                        cx.new(|cx| PersonListItem::new("George", "Harrison", cx)),
+                        cx.new(|cx| PersonListItem::new("Ringo", "Starr", cx)),
+                        cx.new(|cx| PersonListItem::new("David", "Bowie", cx)),
+                        cx.new(|cx| PersonListItem::new("Freddie", "Mercury", cx)),
+                        cx.new(|cx| PersonListItem::new("Elvis", "Presley", cx)),
+                        cx.new(|cx| PersonListItem::new("Bob", "Dylan", cx)),
+                        cx.new(|cx| PersonListItem::new("Jimi", "Hendrix", cx)),
+                        cx.new(|cx| PersonListItem::new("Janis", "Joplin", cx)),
+                        cx.new(|cx| PersonListItem::new("Jim", "Morrison", cx)),
+                        cx.new(|cx| PersonListItem::new("Amy", "Winehouse", cx)),
+                        cx.new(|cx| PersonListItem::new("Whitney", "Houston", cx)),
                     ];
-                    PersonList::new(items)
-                })
+                    PersonList::new(items, window, cx)
+                });
+                // Focused so the theme key bindings work straight away
+                list.focus_handle(cx).focus(window);
+                list
             },
         )
         .unwrap();
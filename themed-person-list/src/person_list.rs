@@ -1,18 +1,146 @@
-use gpui::{Context, Entity, Window, div, prelude::*, uniform_list};
+use gpui::{
+    App, Context, DismissEvent, Entity, FocusHandle, Focusable, KeyBinding, Subscription, Window,
+    actions, div, prelude::*, uniform_list,
+};
 
 use crate::{
     person_list_item::PersonListItem,
-    theme::Theme,
+    theme::{
+        Theme,
+        registry::{self, ThemeRegistry},
+        vision::{self, SEMANTIC_KEYS},
+        watcher::ThemeFileStatus,
+    },
+    theme_picker::{SelectTheme, ThemePicker},
 };
 
+// Show or hide the theme's semantic colors under color-vision deficiencies
+actions!(person_list, [ToggleColorVision]);
+
+pub fn bind_keys(cx: &mut App) {
+    cx.bind_keys([KeyBinding::new("secondary-shift-v", ToggleColorVision, None)]);
+}
+
 // List component to hold multiple PersonListItems
 pub struct PersonList {
     items: Vec<Entity<PersonListItem>>,
+    focus_handle: FocusHandle,
+    theme_picker: Option<(Entity<ThemePicker>, Subscription)>,
+    show_color_vision: bool,
+    _theme_observer: Subscription,
+    _appearance_observer: Subscription,
 }
 
 impl PersonList {
-    pub fn new(items: Vec<Entity<PersonListItem>>) -> Self {
-        Self { items }
+    pub fn new(
+        items: Vec<Entity<PersonListItem>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        // Drives the "system" theme from the light/dark appearance GPUI reports
+        registry::set_appearance(window.appearance(), cx);
+        Self {
+            items,
+            focus_handle: cx.focus_handle(),
+            theme_picker: None,
+            show_color_vision: false,
+            _theme_observer: cx.observe_global::<Theme>(|_, cx| cx.notify()),
+            _appearance_observer: cx.observe_window_appearance(window, |_, window, cx| {
+                registry::set_appearance(window.appearance(), cx);
+            }),
+        }
+    }
+
+    fn toggle_theme_picker(&mut self, _: &SelectTheme, window: &mut Window, cx: &mut Context<Self>) {
+        if self.theme_picker.is_some() {
+            self.close_theme_picker(window, cx);
+        } else {
+            let picker = cx.new(ThemePicker::new);
+            let subscription = cx.subscribe_in(&picker, window, |this, _, _: &DismissEvent, window, cx| {
+                this.theme_picker = None;
+                this.focus_handle.focus(window);
+                cx.notify();
+            });
+            picker.focus_handle(cx).focus(window);
+            self.theme_picker = Some((picker, subscription));
+        }
+        cx.notify();
+    }
+
+    fn toggle_color_vision(
+        &mut self,
+        _: &ToggleColorVision,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.show_color_vision = !self.show_color_vision;
+        cx.notify();
+    }
+
+    // The theme's success, warning, error and info colors as seen with each
+    // color-vision deficiency, flagging rows where two are hard to tell apart
+    fn render_color_vision(&self, theme: &Theme) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .gap_1()
+            .mb_4()
+            .p_3()
+            .rounded_lg()
+            .bg(theme.surface_secondary)
+            .border_1()
+            .border_color(theme.border_subtle)
+            .text_sm()
+            .children(vision::semantic_preview(theme).into_iter().map(|row| {
+                let (first, second, distance) = row.closest;
+                let verdict = if row.is_distinguishable() {
+                    "OK".to_string()
+                } else {
+                    format!(
+                        "⚠ {} and {} ΔE {distance:.1}",
+                        SEMANTIC_KEYS[first], SEMANTIC_KEYS[second]
+                    )
+                };
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child(
+                        div()
+                            .w_20()
+                            .text_color(theme.text_secondary)
+                            .child(row.label()),
+                    )
+                    .children(
+                        row.colors
+                            .map(|color| div().size_5().rounded_sm().bg(color)),
+                    )
+                    .child(
+                        div()
+                            .ml_2()
+                            .text_color(if row.is_distinguishable() {
+                                theme.text_secondary
+                            } else {
+                                theme.text_primary
+                            })
+                            .child(verdict),
+                    )
+            }))
+    }
+
+    // Cancels the picker, putting back the active theme after any preview
+    fn close_theme_picker(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.theme_picker.take().is_some() {
+            registry::restore(cx);
+            self.focus_handle.focus(window);
+            cx.notify();
+        }
+    }
+}
+
+impl Focusable for PersonList {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
     }
 }
 
@@ -21,22 +149,101 @@ impl Render for PersonList {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let items = self.items.clone();
         let theme = cx.global::<Theme>().clone();
+        // Set when the theme file was saved with errors; the last good theme
+        // stays in place until it is fixed
+        let theme_error = cx.try_global::<ThemeFileStatus>().and_then(|status| {
+            let error = status.error.as_ref()?;
+            let file = status.path.file_name().unwrap_or_default().to_string_lossy();
+            Some(format!("Couldn't load {file}: {error}"))
+        });
+
+        let active_theme = cx.default_global::<ThemeRegistry>().active().clone();
 
         div()
+            .key_context("PersonList")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::toggle_theme_picker))
+            .on_action(cx.listener(Self::toggle_color_vision))
+            .relative()
             .flex()
             .flex_col()
             .w_full()
             .h_full()
             .bg(theme.surface_primary)
             .p_6()
+            .when_some(theme_error, |this, error| {
+                this.child(
+                    div()
+                        .mb_4()
+                        .px_3()
+                        .py_2()
+                        .rounded_lg()
+                        .bg(theme.error)
+                        .text_color(theme.text_on_accent)
+                        .text_sm()
+                        .child(error),
+                )
+            })
             .child(
                 div()
-                    .text_2xl()
-                    .font_weight(gpui::FontWeight::BOLD)
-                    .text_color(theme.text_primary)
+                    .flex()
+                    .items_center()
+                    .justify_between()
                     .mb_4()
-                    .child("Person List"),
+                    .child(
+                        div()
+                            .text_2xl()
+                            .font_weight(gpui::FontWeight::BOLD)
+                            .text_color(theme.text_primary)
+                            .child("Person List"),
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .gap_2()
+                            .child(
+                                div()
+                                    .id("color-vision-button")
+                                    .px_3()
+                                    .py_1()
+                                    .rounded_lg()
+                                    .border_1()
+                                    .border_color(theme.border_strong)
+                                    .text_sm()
+                                    .text_color(theme.text_secondary)
+                                    .cursor_pointer()
+                                    .hover(|style| style.bg(theme.surface_tertiary))
+                                    .when(self.show_color_vision, |this| {
+                                        this.bg(theme.surface_tertiary)
+                                    })
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.toggle_color_vision(&ToggleColorVision, window, cx);
+                                    }))
+                                    .child("Color vision"),
+                            )
+                            // Opens the theme picker, same as secondary-k secondary-t
+                            .child(
+                                div()
+                                    .id("theme-button")
+                                    .px_3()
+                                    .py_1()
+                                    .rounded_lg()
+                                    .border_1()
+                                    .border_color(theme.border_strong)
+                                    .text_sm()
+                                    .text_color(theme.text_secondary)
+                                    .cursor_pointer()
+                                    .hover(|style| style.bg(theme.surface_tertiary))
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.toggle_theme_picker(&SelectTheme, window, cx);
+                                    }))
+                                    .child(format!("Theme: {active_theme} ▾")),
+                            ),
+                    ),
             )
+            .when(self.show_color_vision, |this| {
+                this.child(self.render_color_vision(&theme))
+            })
             .child(
                 uniform_list("person-list", items.len(), move |range, _window, _cx| {
                     range
@@ -45,5 +252,46 @@ impl Render for PersonList {
                 })
                 .flex_1(),
             )
+            // A press anywhere outside the picker closes it, including on the
+            // theme button, which the backdrop covers so it can't reopen it
+            .when_some(self.theme_picker.as_ref(), |this, (picker, _)| {
+                this.child(
+                    div()
+                        .id("theme-picker-backdrop")
+                        .absolute()
+                        .inset_0()
+                        .occlude()
+                        .on_any_mouse_down(cx.listener(|this, _, window, cx| {
+                            this.close_theme_picker(window, cx);
+                        })),
+                )
+                .child(div().absolute().top_16().right_6().child(picker.clone()))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::{TestAppContext, WindowAppearance};
+
+    use super::*;
+
+    #[gpui::test]
+    fn system_theme_follows_the_window_appearance(cx: &mut TestAppContext) {
+        cx.update(|cx| registry::set_appearance(WindowAppearance::Dark, cx));
+        assert_eq!(
+            cx.read(|cx| cx.global::<Theme>().surface_primary),
+            Theme::dark().surface_primary
+        );
+
+        // The test platform's windows always report a light appearance
+        cx.add_window(|window, cx| PersonList::new(Vec::new(), window, cx));
+        cx.read(|cx| {
+            assert_eq!(cx.global::<ThemeRegistry>().active(), registry::SYSTEM);
+            assert_eq!(
+                cx.global::<Theme>().surface_primary,
+                Theme::light().surface_primary
+            );
+        });
     }
 }
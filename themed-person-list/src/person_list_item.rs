@@ -1,4 +1,4 @@
-use gpui::{Context, SharedString, Window, div, prelude::*};
+use gpui::{Context, SharedString, Subscription, Window, div, prelude::*};
 
 use crate::theme::Theme;
 
@@ -7,14 +7,21 @@ pub struct PersonListItem {
     first_name: SharedString,
     last_name: SharedString,
     likes: u32,
+    // Re-render when the theme is switched or reloaded
+    _theme_observer: Subscription,
 }
 
 impl PersonListItem {
-    pub fn new(first_name: impl Into<SharedString>, last_name: impl Into<SharedString>) -> Self {
+    pub fn new(
+        first_name: impl Into<SharedString>,
+        last_name: impl Into<SharedString>,
+        cx: &mut Context<Self>,
+    ) -> Self {
         Self {
             first_name: first_name.into(),
             last_name: last_name.into(),
             likes: 0,
+            _theme_observer: cx.observe_global::<Theme>(|_, cx| cx.notify()),
         }
     }
 
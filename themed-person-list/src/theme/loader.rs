@@ -0,0 +1,275 @@
+use std::fmt;
+use std::path::Path;
+
+use gpui::Rgba;
+use gpui_color_mixer::parse::parse_hex_color;
+use serde_json::{Map, Value};
+
+use super::Theme;
+
+/// Color keys a theme file may set, named after the `Theme` fields
+pub const COLOR_KEYS: [&str; 17] = [
+    "surface_primary",
+    "surface_secondary",
+    "surface_tertiary",
+    "surface_elevated",
+    "text_primary",
+    "text_secondary",
+    "text_disabled",
+    "text_on_accent",
+    "accent_primary",
+    "accent_secondary",
+    "accent_hover",
+    "success",
+    "warning",
+    "error",
+    "info",
+    "border_subtle",
+    "border_strong",
+];
+
+fn color_mut<'a>(theme: &'a mut Theme, key: &str) -> Option<&'a mut Rgba> {
+    Some(match key {
+        "surface_primary" => &mut theme.surface_primary,
+        "surface_secondary" => &mut theme.surface_secondary,
+        "surface_tertiary" => &mut theme.surface_tertiary,
+        "surface_elevated" => &mut theme.surface_elevated,
+        "text_primary" => &mut theme.text_primary,
+        "text_secondary" => &mut theme.text_secondary,
+        "text_disabled" => &mut theme.text_disabled,
+        "text_on_accent" => &mut theme.text_on_accent,
+        "accent_primary" => &mut theme.accent_primary,
+        "accent_secondary" => &mut theme.accent_secondary,
+        "accent_hover" => &mut theme.accent_hover,
+        "success" => &mut theme.success,
+        "warning" => &mut theme.warning,
+        "error" => &mut theme.error,
+        "info" => &mut theme.info,
+        "border_subtle" => &mut theme.border_subtle,
+        "border_strong" => &mut theme.border_strong,
+        _ => return None,
+    })
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldError {
+    pub key: String,
+    pub message: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ThemeError {
+    Io(String),
+    UnsupportedFormat(String),
+    Syntax(String),
+    // Every bad key in the file, not just the first
+    Fields(Vec<FieldError>),
+}
+
+impl fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ThemeError::Io(message) | ThemeError::Syntax(message) => write!(f, "{message}"),
+            ThemeError::UnsupportedFormat(extension) => {
+                write!(
+                    f,
+                    "unsupported theme format \"{extension}\", expected toml or json"
+                )
+            }
+            ThemeError::Fields(errors) => {
+                let errors: Vec<String> = errors
+                    .iter()
+                    .map(|error| format!("`{}`: {}", error.key, error.message))
+                    .collect();
+                write!(f, "{}", errors.join("; "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for ThemeError {}
+
+/// The contents of a theme file: the colors it sets and the registered theme
+/// supplying every color it leaves out, dark when `extends` is missing
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ThemeFile {
+    pub name: Option<String>,
+    pub extends: Option<String>,
+    pub colors: Vec<(&'static str, Rgba)>,
+}
+
+impl ThemeFile {
+    /// Reads a `.toml` or `.json` file
+    pub fn read(path: &Path) -> Result<Self, ThemeError> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|error| ThemeError::Io(format!("{}: {error}", path.display())))?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => Self::parse_toml(&text),
+            Some("json") => Self::parse_json(&text),
+            extension => Err(ThemeError::UnsupportedFormat(
+                extension.unwrap_or_default().to_string(),
+            )),
+        }
+    }
+
+    pub fn parse_toml(text: &str) -> Result<Self, ThemeError> {
+        let table: Map<String, Value> =
+            toml::from_str(text).map_err(|error| ThemeError::Syntax(error.to_string()))?;
+        Self::from_map(table)
+    }
+
+    pub fn parse_json(text: &str) -> Result<Self, ThemeError> {
+        match serde_json::from_str(text) {
+            Ok(Value::Object(map)) => Self::from_map(map),
+            Ok(_) => Err(ThemeError::Syntax(
+                "expected an object at the top level".into(),
+            )),
+            Err(error) => Err(ThemeError::Syntax(error.to_string())),
+        }
+    }
+
+    // Both formats share one shape: `name` and `extends` strings plus a hex
+    // string for any of the `COLOR_KEYS`
+    fn from_map(map: Map<String, Value>) -> Result<Self, ThemeError> {
+        let mut file = ThemeFile::default();
+        let mut errors = Vec::new();
+        let mut error = |key: &str, message: String| {
+            errors.push(FieldError {
+                key: key.to_string(),
+                message,
+            })
+        };
+
+        for (key, value) in &map {
+            match key.as_str() {
+                "name" | "extends" => match value {
+                    Value::String(text) if key == "name" => file.name = Some(text.clone()),
+                    Value::String(text) => file.extends = Some(text.clone()),
+                    _ => error(key, format!("expected a string, found {}", describe(value))),
+                },
+                _ => match COLOR_KEYS.iter().find(|candidate| *candidate == key) {
+                    Some(&key) => match value.as_str().map(parse_hex_color) {
+                        Some(Ok(color)) => file.colors.push((key, color)),
+                        _ => error(
+                            key,
+                            format!(
+                                "expected a hex color like \"#1e1e1e\", found {}",
+                                describe(value)
+                            ),
+                        ),
+                    },
+                    None => error(key, "unknown key".to_string()),
+                },
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(file)
+        } else {
+            Err(ThemeError::Fields(errors))
+        }
+    }
+
+    /// `base` with this file's colors laid over it
+    pub fn apply(&self, base: &Theme) -> Theme {
+        let mut theme = base.clone();
+        for (key, color) in &self.colors {
+            *color_mut(&mut theme, key).expect("keys are validated when parsing") = *color;
+        }
+        theme
+    }
+}
+
+fn describe(value: &Value) -> String {
+    match value {
+        Value::String(text) => format!("\"{text}\""),
+        Value::Number(_) => "a number".into(),
+        Value::Bool(_) => "a boolean".into(),
+        Value::Array(_) => "an array".into(),
+        Value::Object(_) => "a table".into(),
+        Value::Null => "null".into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::{rgb, rgba};
+
+    use super::*;
+
+    #[test]
+    fn toml_and_json_override_only_what_they_set() {
+        let toml = ThemeFile::parse_toml(
+            "name = \"Ocean\"\nextends = \"light\"\naccent_primary = \"#006994\"\nborder_subtle = \"#ccc\"\n",
+        )
+        .unwrap();
+        assert_eq!(toml.name.as_deref(), Some("Ocean"));
+        assert_eq!(toml.extends.as_deref(), Some("light"));
+        let theme = toml.apply(&Theme::light());
+        assert_eq!(theme.accent_primary, rgb(0x006994));
+        assert_eq!(theme.border_subtle, rgb(0xcccccc));
+        assert_eq!(theme.surface_primary, Theme::light().surface_primary);
+
+        let json = ThemeFile::parse_json(r##"{ "error": "#ff000080" }"##).unwrap();
+        let theme = json.apply(&Theme::dark());
+        assert_eq!(theme.error, rgba(0xff000080));
+        assert_eq!(theme.accent_primary, Theme::dark().accent_primary);
+    }
+
+    #[test]
+    fn every_bad_key_is_reported_by_name() {
+        let error = ThemeFile::parse_toml(
+            "accent_primary = \"blue\"\nsuccess = 42\nsurface_primery = \"#fff\"\nname = true\n",
+        )
+        .unwrap_err();
+        let ThemeError::Fields(errors) = &error else {
+            panic!("{error:?}");
+        };
+        let mut keys: Vec<&str> = errors.iter().map(|error| error.key.as_str()).collect();
+        keys.sort();
+        assert_eq!(
+            keys,
+            ["accent_primary", "name", "success", "surface_primery"]
+        );
+
+        let message = error.to_string();
+        assert!(
+            message.contains(
+                "`accent_primary`: expected a hex color like \"#1e1e1e\", found \"blue\""
+            )
+        );
+        assert!(
+            message.contains("`success`: expected a hex color like \"#1e1e1e\", found a number")
+        );
+        assert!(message.contains("`surface_primery`: unknown key"));
+    }
+
+    #[test]
+    fn syntax_errors_are_reported() {
+        assert!(matches!(
+            ThemeFile::parse_toml("accent_primary = "),
+            Err(ThemeError::Syntax(_))
+        ));
+        assert!(matches!(
+            ThemeFile::parse_json("[1, 2]"),
+            Err(ThemeError::Syntax(_))
+        ));
+    }
+
+    #[test]
+    fn colors_are_hex_with_a_hash() {
+        let file = ThemeFile::parse_toml("info = \"#abc\"\nerror = \"#ff000080\"").unwrap();
+        let theme = file.apply(&Theme::dark());
+        assert_eq!(theme.info, rgb(0xaabbcc));
+        assert_eq!(theme.error, rgba(0xff000080));
+
+        // The color mixer's other notations aren't part of the format
+        for text in ["1976d2", "red", "rgb(0, 0, 0)"] {
+            let toml = format!("info = \"{text}\"");
+            assert!(matches!(
+                ThemeFile::parse_toml(&toml),
+                Err(ThemeError::Fields(_))
+            ));
+        }
+    }
+}
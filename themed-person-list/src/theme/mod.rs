@@ -1,5 +1,11 @@
 use gpui::{Global, Rgba, rgb};
 
+pub mod loader;
+pub mod registry;
+pub mod settings;
+pub mod vision;
+pub mod watcher;
+
 /// Material Design-inspired theme system with semantic color names
 #[derive(Clone, Debug)]
 
@@ -36,7 +42,6 @@ pub struct Theme {
 impl Global for Theme {}
 
 impl Theme {
-    #[allow(dead_code)]
     pub fn light() -> Self {
         Self {
             // Light surfaces
@@ -68,7 +73,6 @@ impl Theme {
         }
     }
 
-    #[allow(dead_code)]
     pub fn dark() -> Self {
         Self {
             // Dark surfaces
@@ -0,0 +1,428 @@
+use std::fmt;
+
+use gpui::{App, Global, SharedString, WindowAppearance, rgb};
+
+use super::Theme;
+use super::loader::ThemeFile;
+
+/// Selecting this instead of a theme name follows the system's light or dark
+/// appearance; picking any registered theme overrides it
+pub const SYSTEM: &str = "system";
+
+/// Themes every registry starts with, which theme files can't replace
+pub const BUILT_IN: [&str; 4] = ["dark", "light", "dark-high-contrast", "light-high-contrast"];
+
+enum Definition {
+    Complete(Theme),
+    // Only the colors that differ from the theme named by `extends`
+    Variant(ThemeFile),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum RegistryError {
+    UnknownTheme(SharedString),
+    UnknownParent {
+        theme: SharedString,
+        parent: SharedString,
+    },
+    // The themes around the loop, starting and ending with the same one
+    Cycle(Vec<SharedString>),
+    // A built-in theme or `SYSTEM`
+    Reserved(SharedString),
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RegistryError::UnknownTheme(name) => write!(f, "unknown theme \"{name}\""),
+            RegistryError::UnknownParent { theme, parent } => {
+                write!(f, "\"{theme}\" extends unknown theme \"{parent}\"")
+            }
+            RegistryError::Cycle(names) => {
+                let names: Vec<&str> = names.iter().map(|name| name.as_ref()).collect();
+                write!(
+                    f,
+                    "themes extend each other in a loop: {}",
+                    names.join(" -> ")
+                )
+            }
+            RegistryError::Reserved(name) => {
+                write!(f, "\"{name}\" is reserved for a built-in theme")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RegistryError {}
+
+/// Every theme that can be picked, in the order they are listed, and the one
+/// in use. A theme is either complete or a variant that extends another and
+/// overrides some of its colors, like "dark-high-contrast".
+pub struct ThemeRegistry {
+    themes: Vec<(SharedString, Definition)>,
+    // A theme name or `SYSTEM`
+    active: SharedString,
+    // Last appearance reported by the platform
+    appearance: WindowAppearance,
+}
+
+impl Global for ThemeRegistry {}
+
+impl Default for ThemeRegistry {
+    fn default() -> Self {
+        let mut registry = Self {
+            themes: Vec::new(),
+            active: SYSTEM.into(),
+            appearance: WindowAppearance::Dark,
+        };
+        registry.register("dark", Theme::dark());
+        registry.register("light", Theme::light());
+        registry
+            .register_variant(
+                "dark-high-contrast",
+                ThemeFile {
+                    name: None,
+                    extends: Some("dark".into()),
+                    colors: vec![
+                        ("surface_primary", rgb(0x000000)),
+                        ("surface_secondary", rgb(0x0A0A0A)),
+                        ("text_secondary", rgb(0xE0E0E0)),
+                        ("text_on_accent", rgb(0x000000)),
+                        ("border_subtle", rgb(0x8A8A8A)),
+                        ("border_strong", rgb(0xFFFFFF)),
+                    ],
+                },
+            )
+            .expect("extends a built-in theme");
+        registry
+            .register_variant(
+                "light-high-contrast",
+                ThemeFile {
+                    name: None,
+                    extends: Some("light".into()),
+                    colors: vec![
+                        ("text_primary", rgb(0x000000)),
+                        ("text_secondary", rgb(0x333333)),
+                        ("accent_primary", rgb(0x0D47A1)),
+                        ("accent_hover", rgb(0x08306B)),
+                        ("border_subtle", rgb(0x767676)),
+                        ("border_strong", rgb(0x000000)),
+                    ],
+                },
+            )
+            .expect("extends a built-in theme");
+        registry
+    }
+}
+
+impl ThemeRegistry {
+    /// Adds a theme, replacing any already registered under `name`
+    pub fn register(&mut self, name: impl Into<SharedString>, theme: Theme) {
+        self.insert(name.into(), Definition::Complete(theme));
+    }
+
+    /// Adds a theme made of `file`'s colors over the theme it extends, dark
+    /// when it names none. Registering something that doesn't resolve leaves
+    /// the registry as it was.
+    pub fn register_variant(
+        &mut self,
+        name: impl Into<SharedString>,
+        file: ThemeFile,
+    ) -> Result<(), RegistryError> {
+        let name = name.into();
+        let previous = self.insert(name.clone(), Definition::Variant(file));
+        if let Err(error) = self.theme(&name) {
+            match previous {
+                Some(definition) => {
+                    self.insert(name, definition);
+                }
+                None => self.themes.retain(|(existing, _)| *existing != name),
+            }
+            return Err(error);
+        }
+        Ok(())
+    }
+
+    /// Removes the theme registered as `name`, if any. Themes extending it
+    /// stop resolving until it is registered again.
+    pub fn unregister(&mut self, name: &str) {
+        self.themes.retain(|(existing, _)| existing != name);
+    }
+
+    /// Whether `name` is `SYSTEM` or one of the `BUILT_IN` themes
+    pub fn is_reserved(name: &str) -> bool {
+        name == SYSTEM || BUILT_IN.contains(&name)
+    }
+
+    // Replaces in place to keep the listed order, returning what was there
+    fn insert(&mut self, name: SharedString, definition: Definition) -> Option<Definition> {
+        match self
+            .themes
+            .iter_mut()
+            .find(|(existing, _)| *existing == name)
+        {
+            Some(entry) => Some(std::mem::replace(&mut entry.1, definition)),
+            None => {
+                self.themes.push((name, definition));
+                None
+            }
+        }
+    }
+
+    fn definition(&self, name: &str) -> Option<&Definition> {
+        self.themes
+            .iter()
+            .find(|(existing, _)| existing == name)
+            .map(|(_, definition)| definition)
+    }
+
+    /// The complete theme registered as `name`, following `extends` until it
+    /// reaches a complete theme and laying each variant's colors over it
+    pub fn theme(&self, name: &str) -> Result<Theme, RegistryError> {
+        let mut variants = Vec::new();
+        let mut visited: Vec<SharedString> = Vec::new();
+        let mut current = name;
+        loop {
+            if let Some(start) = visited.iter().position(|seen| seen == current) {
+                let mut cycle = visited.split_off(start);
+                cycle.push(current.to_string().into());
+                return Err(RegistryError::Cycle(cycle));
+            }
+            let definition = self
+                .definition(current)
+                .ok_or_else(|| match visited.last() {
+                    Some(theme) => RegistryError::UnknownParent {
+                        theme: theme.clone(),
+                        parent: current.to_string().into(),
+                    },
+                    None => RegistryError::UnknownTheme(current.to_string().into()),
+                })?;
+            visited.push(current.to_string().into());
+
+            match definition {
+                Definition::Complete(theme) => {
+                    return Ok(variants
+                        .iter()
+                        .rev()
+                        .fold(theme.clone(), |theme, file: &&ThemeFile| file.apply(&theme)));
+                }
+                Definition::Variant(file) => {
+                    variants.push(file);
+                    current = file.extends.as_deref().unwrap_or("dark");
+                }
+            }
+        }
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &SharedString> {
+        self.themes.iter().map(|(name, _)| name)
+    }
+
+    pub fn active(&self) -> &SharedString {
+        &self.active
+    }
+
+    /// The theme `name` stands for: itself, or light or dark for `SYSTEM`
+    pub fn resolve<'a>(&self, name: &'a str) -> &'a str {
+        match (name, self.appearance) {
+            (SYSTEM, WindowAppearance::Light | WindowAppearance::VibrantLight) => "light",
+            (SYSTEM, WindowAppearance::Dark | WindowAppearance::VibrantDark) => "dark",
+            _ => name,
+        }
+    }
+}
+
+/// Makes a registered theme, or `SYSTEM`, the current one; unknown names are
+/// ignored and return false
+pub fn select(name: &str, cx: &mut App) -> bool {
+    let registry = cx.default_global::<ThemeRegistry>();
+    let Ok(theme) = registry.theme(registry.resolve(name)) else {
+        return false;
+    };
+    registry.active = SharedString::from(name.to_string());
+    cx.set_global(theme);
+    true
+}
+
+/// Shows a registered theme without making it the active one
+pub fn preview(name: &str, cx: &mut App) {
+    let registry = cx.default_global::<ThemeRegistry>();
+    if let Ok(theme) = registry.theme(registry.resolve(name)) {
+        cx.set_global(theme);
+    }
+}
+
+/// Puts the active theme back after a preview
+pub fn restore(cx: &mut App) {
+    let active = cx.default_global::<ThemeRegistry>().active.clone();
+    select(&active, cx);
+}
+
+/// Records a change in the system appearance, switching theme when
+/// following it
+pub fn set_appearance(appearance: WindowAppearance, cx: &mut App) {
+    let registry = cx.default_global::<ThemeRegistry>();
+    registry.appearance = appearance;
+    if registry.active == SYSTEM {
+        select(SYSTEM, cx);
+    }
+}
+
+/// Switches between the built-in light and dark themes, overriding the system
+/// appearance
+pub fn toggle(cx: &mut App) {
+    let registry = cx.default_global::<ThemeRegistry>();
+    let next = if registry.resolve(&registry.active) == "light" {
+        "dark"
+    } else {
+        "light"
+    };
+    select(next, cx);
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::TestAppContext;
+
+    use super::*;
+
+    fn surface(cx: &App) -> gpui::Rgba {
+        cx.global::<Theme>().surface_primary
+    }
+
+    #[gpui::test]
+    fn previews_are_undone_by_restore(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let mut high_contrast = Theme::dark();
+            high_contrast.surface_primary = gpui::rgb(0x000000);
+            cx.default_global::<ThemeRegistry>()
+                .register("high-contrast", high_contrast);
+            assert!(select("dark", cx));
+            assert!(!select("missing", cx));
+            assert_eq!(cx.global::<ThemeRegistry>().active(), "dark");
+
+            preview("high-contrast", cx);
+            assert_eq!(surface(cx), gpui::rgb(0x000000));
+            assert_eq!(cx.global::<ThemeRegistry>().active(), "dark");
+            restore(cx);
+            assert_eq!(surface(cx), Theme::dark().surface_primary);
+
+            assert!(select("high-contrast", cx));
+            assert_eq!(cx.global::<ThemeRegistry>().active(), "high-contrast");
+            let names: Vec<_> = cx.global::<ThemeRegistry>().names().cloned().collect();
+            assert_eq!(names[..BUILT_IN.len()], BUILT_IN);
+            assert_eq!(names[BUILT_IN.len()..], ["high-contrast"]);
+        });
+    }
+
+    fn variant(extends: &str, colors: Vec<(&'static str, gpui::Rgba)>) -> ThemeFile {
+        ThemeFile {
+            name: None,
+            extends: Some(extends.into()),
+            colors,
+        }
+    }
+
+    #[test]
+    fn variants_override_only_their_own_colors() {
+        let mut registry = ThemeRegistry::default();
+        let high_contrast = registry.theme("dark-high-contrast").unwrap();
+        assert_eq!(high_contrast.surface_primary, rgb(0x000000));
+        assert_eq!(high_contrast.accent_primary, Theme::dark().accent_primary);
+
+        // Each level applies over the one it extends
+        registry
+            .register_variant(
+                "midnight",
+                variant(
+                    "dark-high-contrast",
+                    vec![("accent_primary", rgb(0x7C4DFF))],
+                ),
+            )
+            .unwrap();
+        let midnight = registry.theme("midnight").unwrap();
+        assert_eq!(midnight.accent_primary, rgb(0x7C4DFF));
+        assert_eq!(midnight.surface_primary, rgb(0x000000));
+        assert_eq!(midnight.text_primary, Theme::dark().text_primary);
+
+        // Later changes to a parent show through
+        registry.register("dark", Theme::light());
+        assert_eq!(
+            registry.theme("midnight").unwrap().text_primary,
+            Theme::light().text_primary
+        );
+    }
+
+    #[test]
+    fn cycles_and_missing_parents_are_rejected() {
+        let mut registry = ThemeRegistry::default();
+        registry
+            .register_variant("a", variant("dark", vec![]))
+            .unwrap();
+        registry
+            .register_variant("b", variant("a", vec![]))
+            .unwrap();
+
+        let error = registry
+            .register_variant("a", variant("b", vec![]))
+            .unwrap_err();
+        assert_eq!(
+            error,
+            RegistryError::Cycle(vec!["a".into(), "b".into(), "a".into()])
+        );
+        assert_eq!(
+            error.to_string(),
+            "themes extend each other in a loop: a -> b -> a"
+        );
+        // The rejected definition is rolled back
+        assert!(registry.theme("b").is_ok());
+
+        assert_eq!(
+            registry.register_variant("c", variant("c", vec![])),
+            Err(RegistryError::Cycle(vec!["c".into(), "c".into()]))
+        );
+        assert_eq!(
+            registry.register_variant("d", variant("solarized", vec![])),
+            Err(RegistryError::UnknownParent {
+                theme: "d".into(),
+                parent: "solarized".into(),
+            })
+        );
+        assert!(registry.names().all(|name| name != "c" && name != "d"));
+        assert_eq!(
+            registry.theme("e").unwrap_err(),
+            RegistryError::UnknownTheme("e".into())
+        );
+    }
+
+    #[gpui::test]
+    fn system_follows_the_appearance_until_overridden(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            set_appearance(WindowAppearance::VibrantLight, cx);
+            assert_eq!(cx.global::<ThemeRegistry>().active(), SYSTEM);
+            assert_eq!(surface(cx), Theme::light().surface_primary);
+            set_appearance(WindowAppearance::Dark, cx);
+            assert_eq!(surface(cx), Theme::dark().surface_primary);
+
+            assert!(select("light", cx));
+            set_appearance(WindowAppearance::Dark, cx);
+            assert_eq!(surface(cx), Theme::light().surface_primary);
+
+            assert!(select(SYSTEM, cx));
+            assert_eq!(surface(cx), Theme::dark().surface_primary);
+        });
+    }
+
+    #[gpui::test]
+    fn toggle_flips_between_light_and_dark(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            // From whatever the system shows, then as an override
+            set_appearance(WindowAppearance::Dark, cx);
+            toggle(cx);
+            assert_eq!(cx.global::<ThemeRegistry>().active(), "light");
+            assert_eq!(surface(cx), Theme::light().surface_primary);
+            toggle(cx);
+            assert_eq!(cx.global::<ThemeRegistry>().active(), "dark");
+        });
+    }
+}
@@ -0,0 +1,82 @@
+use std::path::{Path, PathBuf};
+
+use gpui::{App, Global};
+use serde_json::{Value, json};
+
+use super::registry::ThemeRegistry;
+
+/// Where the theme picked in the app is kept between runs
+pub struct ThemeSettings {
+    pub path: PathBuf,
+}
+
+impl Global for ThemeSettings {}
+
+/// `$XDG_CONFIG_HOME/themed-person-list/settings.json` on Linux, and the
+/// platform's equivalent elsewhere
+pub fn default_path() -> Option<PathBuf> {
+    Some(
+        dirs::config_dir()?
+            .join("themed-person-list")
+            .join("settings.json"),
+    )
+}
+
+/// The saved theme name, a registered theme or "system". A missing or
+/// unreadable file means nothing was saved.
+pub fn load(path: &Path) -> Option<String> {
+    let text = std::fs::read_to_string(path).ok()?;
+    match serde_json::from_str(&text).ok()? {
+        Value::Object(map) => map.get("theme")?.as_str().map(str::to_string),
+        _ => None,
+    }
+}
+
+pub fn save(path: &Path, theme: &str) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let text = serde_json::to_string_pretty(&json!({ "theme": theme }))?;
+    std::fs::write(path, text + "\n")
+}
+
+/// Saves the active theme so the next run starts with it. Only choices the
+/// user makes are saved, not previews or `--theme`.
+pub fn remember(cx: &App) {
+    let (Some(settings), Some(registry)) = (
+        cx.try_global::<ThemeSettings>(),
+        cx.try_global::<ThemeRegistry>(),
+    ) else {
+        return;
+    };
+    if let Err(error) = save(&settings.path, registry.active()) {
+        eprintln!(
+            "can't save settings to {}: {error}",
+            settings.path.display()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saves_and_loads_the_theme() {
+        let dir = std::env::temp_dir().join(format!(
+            "themed-person-list-settings-{}",
+            std::process::id()
+        ));
+        let path = dir.join("settings.json");
+        assert_eq!(load(&path), None);
+
+        save(&path, "dark-high-contrast").unwrap();
+        assert_eq!(load(&path).as_deref(), Some("dark-high-contrast"));
+        save(&path, "system").unwrap();
+        assert_eq!(load(&path).as_deref(), Some("system"));
+
+        std::fs::write(&path, "{ \"theme\": 3 }").unwrap();
+        assert_eq!(load(&path), None);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,94 @@
+use gpui::Rgba;
+use gpui_color_mixer::color::Lab;
+use gpui_color_mixer::distance::delta_e_2000;
+use gpui_color_mixer::vision::Deficiency;
+
+use super::Theme;
+
+/// Below this CIEDE2000 difference two semantic colors are easy to confuse
+pub const MIN_DISTANCE: f32 = 10.0;
+
+pub const SEMANTIC_KEYS: [&str; 4] = ["success", "warning", "error", "info"];
+
+/// A theme's semantic colors as seen with one color-vision deficiency, or
+/// with typical vision when `deficiency` is `None`
+pub struct SemanticPreview {
+    pub deficiency: Option<Deficiency>,
+    // In `SEMANTIC_KEYS` order
+    pub colors: [Rgba; 4],
+    // Indices of the two colors closest together, and their distance
+    pub closest: (usize, usize, f32),
+}
+
+impl SemanticPreview {
+    fn new(theme: &Theme, deficiency: Option<Deficiency>) -> Self {
+        let colors = [theme.success, theme.warning, theme.error, theme.info]
+            .map(|color| gpui_color_mixer::vision::simulate(deficiency, color));
+        let mut closest = (0, 1, f32::MAX);
+        for first in 0..colors.len() {
+            for second in first + 1..colors.len() {
+                let distance = delta_e_2000(Lab::from(colors[first]), Lab::from(colors[second]));
+                if distance < closest.2 {
+                    closest = (first, second, distance);
+                }
+            }
+        }
+        Self {
+            deficiency,
+            colors,
+            closest,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        self.deficiency.map_or("Typical", Deficiency::label)
+    }
+
+    pub fn is_distinguishable(&self) -> bool {
+        self.closest.2 >= MIN_DISTANCE
+    }
+}
+
+/// Typical vision followed by each simulated deficiency
+pub fn semantic_preview(theme: &Theme) -> Vec<SemanticPreview> {
+    std::iter::once(None)
+        .chain(Deficiency::ALL.map(Some))
+        .map(|deficiency| SemanticPreview::new(theme, deficiency))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::rgb;
+
+    use super::*;
+
+    #[test]
+    fn previews_every_deficiency() {
+        let rows = semantic_preview(&Theme::light());
+        assert_eq!(rows.len(), 1 + Deficiency::ALL.len());
+        assert_eq!(rows[0].colors[0], Theme::light().success);
+        assert!(rows[0].is_distinguishable());
+
+        // Without color vision only lightness is left
+        let achromatic = &rows[4];
+        assert_eq!(achromatic.label(), "Achroma");
+        for color in achromatic.colors {
+            assert!((color.r - color.g).abs() < 0.01 && (color.g - color.b).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn flags_colors_that_collapse_together() {
+        // Red and green of equal lightness look alike to a deutan
+        let mut theme = Theme::dark();
+        theme.success = rgb(0x4caf50);
+        theme.error = rgb(0xc0704a);
+        let rows = semantic_preview(&theme);
+        assert!(rows[0].is_distinguishable());
+        let deutan = &rows[2];
+        assert_eq!(deutan.deficiency, Some(Deficiency::Deuteranopia));
+        assert_eq!((deutan.closest.0, deutan.closest.1), (0, 2));
+        assert!(!deutan.is_distinguishable(), "{}", deutan.closest.2);
+    }
+}
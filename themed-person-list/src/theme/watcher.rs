@@ -0,0 +1,263 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use gpui::{App, Global, SharedString};
+
+use super::Theme;
+use super::loader::{ThemeError, ThemeFile};
+use super::registry::{self, RegistryError, ThemeRegistry};
+
+// How often the theme file's modification time is checked
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The theme file being watched, the name its theme is registered under once
+/// it has loaded, and why it last failed to load if it did
+pub struct ThemeFileStatus {
+    pub path: PathBuf,
+    pub name: Option<SharedString>,
+    pub error: Option<SharedString>,
+}
+
+impl Global for ThemeFileStatus {}
+
+/// Loads the theme at `path` and reloads it whenever the file is saved, for
+/// as long as the app runs. The first load happens right away so there is a
+/// theme before any window opens; checking for saves and rereading the file
+/// happen off the main thread.
+pub fn watch(path: PathBuf, cx: &mut App) {
+    let mut last_modified = modified(&path);
+    cx.set_global(ThemeFileStatus {
+        path: path.clone(),
+        name: None,
+        error: None,
+    });
+    apply(ThemeFile::read(&path), cx);
+
+    cx.spawn(async move |cx| {
+        loop {
+            cx.background_executor().timer(POLL_INTERVAL).await;
+            let changed = cx
+                .background_executor()
+                .spawn({
+                    let path = path.clone();
+                    async move {
+                        // A missing file is usually an editor midway through
+                        // replacing it
+                        let current = modified(&path)?;
+                        (Some(current) != last_modified).then(|| (current, ThemeFile::read(&path)))
+                    }
+                })
+                .await;
+            let Some((current, result)) = changed else {
+                continue;
+            };
+            last_modified = Some(current);
+
+            if cx.update(|cx| apply(result, cx)).is_err() {
+                break;
+            }
+        }
+    })
+    .detach();
+}
+
+/// Registers a freshly loaded theme as a variant of the theme it extends.
+/// The first load switches to it; later saves only show when it is still the
+/// active theme, leaving a `--theme` or picked theme alone. On failure,
+/// including a bad `extends` or a built-in name, the current theme stays, or
+/// the built-in dark one if there is none yet, and the error is kept for the
+/// banner.
+pub fn apply(result: Result<ThemeFile, ThemeError>, cx: &mut App) {
+    let previous = cx
+        .try_global::<ThemeFileStatus>()
+        .and_then(|status| status.name.clone());
+    // Listed in the picker under its `name`, or else the file's
+    let registered = result.map_err(|error| error.to_string()).and_then(|file| {
+        let name: SharedString = match (&file.name, cx.try_global::<ThemeFileStatus>()) {
+            (Some(name), _) => name.clone().into(),
+            (None, Some(status)) => theme_name(&status.path).into(),
+            (None, None) => "custom".into(),
+        };
+        if ThemeRegistry::is_reserved(&name) {
+            return Err(RegistryError::Reserved(name).to_string());
+        }
+        let registry = cx.default_global::<ThemeRegistry>();
+        registry
+            .register_variant(name.clone(), file)
+            .map_err(|error| error.to_string())?;
+        // A renamed theme replaces the old entry rather than adding another
+        if let Some(previous) = previous.as_ref().filter(|previous| **previous != name) {
+            registry.unregister(previous);
+        }
+        Ok(name)
+    });
+    let (name, error) = match registered {
+        Ok(name) => {
+            // Reselecting the active theme picks up the new colors, including
+            // through themes that extend this one
+            let active = cx.default_global::<ThemeRegistry>().active().clone();
+            if previous.is_none() || previous.as_ref() == Some(&active) {
+                registry::select(&name, cx);
+            } else {
+                registry::select(&active, cx);
+            }
+            (Some(name), None)
+        }
+        Err(error) => {
+            if !cx.has_global::<Theme>() {
+                cx.set_global(Theme::dark());
+            }
+            (previous, Some(error.into()))
+        }
+    };
+    if let Some(status) = cx.try_global::<ThemeFileStatus>() {
+        let path = status.path.clone();
+        cx.set_global(ThemeFileStatus { path, name, error });
+    }
+    cx.refresh_windows();
+}
+
+fn theme_name(path: &Path) -> String {
+    path.file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).ok()?.modified().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use gpui::{TestAppContext, rgb};
+
+    use super::*;
+
+    fn write(path: &Path, text: &str, modified: SystemTime) {
+        std::fs::write(path, text).unwrap();
+        File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+    }
+
+    #[gpui::test]
+    fn reloads_on_save_and_keeps_the_last_good_theme(cx: &mut TestAppContext) {
+        let dir = std::env::temp_dir().join(format!("themed-person-list-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("theme.toml");
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        write(&path, "accent_primary = \"#111111\"", start);
+
+        cx.update(|cx| watch(path.clone(), cx));
+        let accent = |cx: &mut TestAppContext| cx.read(|cx| cx.global::<Theme>().accent_primary);
+        let error =
+            |cx: &mut TestAppContext| cx.read(|cx| cx.global::<ThemeFileStatus>().error.clone());
+        assert_eq!(accent(cx), rgb(0x111111));
+
+        write(
+            &path,
+            "accent_primary = \"#222222\"",
+            start + Duration::from_secs(1),
+        );
+        cx.executor().advance_clock(POLL_INTERVAL);
+        assert_eq!(accent(cx), rgb(0x222222));
+        assert_eq!(error(cx), None);
+
+        write(
+            &path,
+            "accent_primary = \"red\"",
+            start + Duration::from_secs(2),
+        );
+        cx.executor().advance_clock(POLL_INTERVAL);
+        assert_eq!(accent(cx), rgb(0x222222));
+        assert!(error(cx).unwrap().contains("accent_primary"));
+
+        // Files can build on any registered theme, but not on themselves
+        write(
+            &path,
+            "extends = \"dark-high-contrast\"\naccent_primary = \"#333333\"",
+            start + Duration::from_secs(3),
+        );
+        cx.executor().advance_clock(POLL_INTERVAL);
+        assert_eq!(accent(cx), rgb(0x333333));
+        assert_eq!(
+            cx.read(|cx| cx.global::<Theme>().surface_primary),
+            rgb(0x000000)
+        );
+
+        write(&path, "extends = \"theme\"", start + Duration::from_secs(4));
+        cx.executor().advance_clock(POLL_INTERVAL);
+        assert_eq!(accent(cx), rgb(0x333333));
+        assert!(error(cx).unwrap().contains("loop"));
+
+        // Built-in themes can't be replaced, and renaming drops the old entry
+        write(
+            &path,
+            "name = \"dark\"\naccent_primary = \"#444444\"",
+            start + Duration::from_secs(5),
+        );
+        cx.executor().advance_clock(POLL_INTERVAL);
+        assert_eq!(accent(cx), rgb(0x333333));
+        assert!(error(cx).unwrap().contains("reserved"));
+        cx.read(|cx| {
+            let dark = cx.global::<ThemeRegistry>().theme("dark").unwrap();
+            assert_eq!(dark.accent_primary, Theme::dark().accent_primary);
+        });
+
+        write(
+            &path,
+            "name = \"ocean\"\naccent_primary = \"#555555\"",
+            start + Duration::from_secs(6),
+        );
+        cx.executor().advance_clock(POLL_INTERVAL);
+        assert_eq!(accent(cx), rgb(0x555555));
+        cx.read(|cx| {
+            let names: Vec<_> = cx.global::<ThemeRegistry>().names().cloned().collect();
+            assert!(names.iter().any(|name| name == "ocean"));
+            assert!(names.iter().all(|name| name != "theme"));
+        });
+
+        // Once another theme is picked, saves no longer switch back
+        cx.update(|cx| registry::select("light", cx));
+        write(
+            &path,
+            "name = \"ocean\"\naccent_primary = \"#666666\"",
+            start + Duration::from_secs(7),
+        );
+        cx.executor().advance_clock(POLL_INTERVAL);
+        assert_eq!(accent(cx), Theme::light().accent_primary);
+        cx.read(|cx| {
+            let registry = cx.global::<ThemeRegistry>();
+            assert_eq!(registry.active(), "light");
+            assert_eq!(
+                registry.theme("ocean").unwrap().accent_primary,
+                rgb(0x666666)
+            );
+        });
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[gpui::test]
+    fn a_bad_first_load_falls_back_to_dark(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            cx.set_global(ThemeFileStatus {
+                path: "missing.toml".into(),
+                name: None,
+                error: None,
+            });
+            apply(Err(ThemeError::Syntax("nope".into())), cx);
+            assert_eq!(
+                cx.global::<Theme>().surface_primary,
+                Theme::dark().surface_primary
+            );
+            assert_eq!(cx.global::<ThemeFileStatus>().error, Some("nope".into()));
+        });
+    }
+}
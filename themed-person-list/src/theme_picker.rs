@@ -0,0 +1,172 @@
+use gpui::{
+    App, Context, DismissEvent, EventEmitter, FocusHandle, Focusable, KeyBinding, SharedString,
+    Window, actions, div, prelude::*, px,
+};
+
+use crate::theme::{
+    Theme,
+    registry::{self, SYSTEM, ThemeRegistry},
+    settings,
+};
+
+// Flip between light and dark, or open the picker
+actions!(theme, [ToggleTheme, SelectTheme]);
+actions!(theme_picker, [SelectNext, SelectPrevious, Confirm, Dismiss]);
+
+pub fn bind_keys(cx: &mut App) {
+    cx.bind_keys([
+        KeyBinding::new("secondary-shift-t", ToggleTheme, None),
+        KeyBinding::new("secondary-k secondary-t", SelectTheme, None),
+        KeyBinding::new("down", SelectNext, Some("ThemePicker")),
+        KeyBinding::new("up", SelectPrevious, Some("ThemePicker")),
+        KeyBinding::new("enter", Confirm, Some("ThemePicker")),
+        KeyBinding::new("escape", Dismiss, Some("ThemePicker")),
+    ]);
+}
+
+/// Lists "system" and every registered theme. Hovering one or moving to it
+/// with the arrow keys previews it; clicking or pressing enter keeps it, and
+/// dismissing puts the active theme back. Whoever shows the picker closes it
+/// on clicks outside.
+pub struct ThemePicker {
+    focus_handle: FocusHandle,
+    names: Vec<SharedString>,
+    selected: usize,
+}
+
+impl EventEmitter<DismissEvent> for ThemePicker {}
+
+impl ThemePicker {
+    pub fn new(cx: &mut Context<Self>) -> Self {
+        let registry = cx.default_global::<ThemeRegistry>();
+        let names: Vec<SharedString> = std::iter::once(SYSTEM.into())
+            .chain(registry.names().cloned())
+            .collect();
+        let selected = names
+            .iter()
+            .position(|name| name == registry.active())
+            .unwrap_or(0);
+        Self {
+            focus_handle: cx.focus_handle(),
+            names,
+            selected,
+        }
+    }
+
+    fn preview(&mut self, index: usize, cx: &mut Context<Self>) {
+        self.selected = index;
+        registry::preview(&self.names[index], cx);
+        cx.notify();
+    }
+
+    fn select_next(&mut self, _: &SelectNext, _window: &mut Window, cx: &mut Context<Self>) {
+        if !self.names.is_empty() {
+            self.preview((self.selected + 1) % self.names.len(), cx);
+        }
+    }
+
+    fn select_previous(
+        &mut self,
+        _: &SelectPrevious,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.names.is_empty() {
+            self.preview(
+                (self.selected + self.names.len() - 1) % self.names.len(),
+                cx,
+            );
+        }
+    }
+
+    fn confirm(&mut self, _: &Confirm, _window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(name) = self.names.get(self.selected) {
+            registry::select(name, cx);
+            settings::remember(cx);
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn dismiss(&mut self, _: &Dismiss, _window: &mut Window, cx: &mut Context<Self>) {
+        registry::restore(cx);
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Focusable for ThemePicker {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for ThemePicker {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = cx.global::<Theme>().clone();
+        let registry = cx.default_global::<ThemeRegistry>();
+        let active = registry.active().clone();
+        let system = format!("{SYSTEM} ({})", registry.resolve(SYSTEM));
+
+        div()
+            .id("theme-picker")
+            .key_context("ThemePicker")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::dismiss))
+            // Moving off the list ends the preview
+            .on_hover(cx.listener(|this, hovered: &bool, _, cx| {
+                if !*hovered {
+                    let active = cx.default_global::<ThemeRegistry>().active().clone();
+                    this.selected = this
+                        .names
+                        .iter()
+                        .position(|name| *name == active)
+                        .unwrap_or(0);
+                    registry::restore(cx);
+                    cx.notify();
+                }
+            }))
+            .w(px(220.0))
+            .p_1()
+            .flex()
+            .flex_col()
+            .bg(theme.surface_elevated)
+            .border_1()
+            .border_color(theme.border_strong)
+            .rounded_lg()
+            .shadow_lg()
+            .children(self.names.iter().enumerate().map(|(index, name)| {
+                let selected = index == self.selected;
+                div()
+                    .id(index)
+                    .flex()
+                    .justify_between()
+                    .px_3()
+                    .py_1()
+                    .rounded_md()
+                    .cursor_pointer()
+                    .text_color(if selected {
+                        theme.text_on_accent
+                    } else {
+                        theme.text_primary
+                    })
+                    .when(selected, |this| this.bg(theme.accent_primary))
+                    .on_hover(cx.listener(move |this, hovered: &bool, _, cx| {
+                        if *hovered {
+                            this.preview(index, cx);
+                        }
+                    }))
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.selected = index;
+                        this.confirm(&Confirm, window, cx);
+                    }))
+                    .child(if name == SYSTEM {
+                        system.clone().into()
+                    } else {
+                        name.clone()
+                    })
+                    .when(*name == active, |this| this.child("✓"))
+            }))
+    }
+}